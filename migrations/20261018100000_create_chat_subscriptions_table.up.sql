@@ -0,0 +1,5 @@
+create table if not exists "chat_subscriptions" (
+    "chat_id" bigint not null primary key,
+    "subscribed_by" bigint,
+    "created_at" timestamp with time zone not null default current_timestamp
+);
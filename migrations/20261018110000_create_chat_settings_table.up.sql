@@ -0,0 +1,9 @@
+create table if not exists "chat_settings" (
+    "chat_id" bigint not null primary key,
+    "timezone" text not null default 'Europe/Moscow',
+    "weekday" smallint not null default 5,
+    "start_time" time not null default '00:00',
+    "end_time" time not null default '00:00',
+    "created_at" timestamp with time zone not null default current_timestamp,
+    "updated_at" timestamp with time zone not null default current_timestamp
+);
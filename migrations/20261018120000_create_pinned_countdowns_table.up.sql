@@ -0,0 +1,6 @@
+create table if not exists "pinned_countdowns" (
+    "chat_id" bigint not null primary key,
+    "message_id" integer not null,
+    "created_at" timestamp with time zone not null default current_timestamp,
+    "updated_at" timestamp with time zone not null default current_timestamp
+);
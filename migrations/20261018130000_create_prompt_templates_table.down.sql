@@ -0,0 +1,3 @@
+alter table "chat_settings" drop column if exists "prompt_template";
+
+drop table if exists "prompt_templates";
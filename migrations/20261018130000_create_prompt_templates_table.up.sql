@@ -0,0 +1,9 @@
+create table if not exists "prompt_templates" (
+    "name" text not null primary key,
+    "body" text not null,
+    "created_by" bigint,
+    "created_at" timestamp with time zone not null default current_timestamp,
+    "updated_at" timestamp with time zone not null default current_timestamp
+);
+
+alter table "chat_settings" add column if not exists "prompt_template" text;
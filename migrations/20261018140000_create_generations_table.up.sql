@@ -0,0 +1,14 @@
+create table if not exists "generations" (
+    "id" uuid not null primary key,
+    "chat_id" bigint not null,
+    "message_id" integer not null,
+    "model" text not null,
+    "prompt_template" text not null,
+    "input_text" text not null,
+    "output_text" text not null,
+    "latency_ms" integer not null,
+    "created_at" timestamp with time zone not null default current_timestamp
+);
+
+create index if not exists "generations_chat_message_idx" on "generations" ("chat_id", "message_id");
+create index if not exists "generations_created_at_idx" on "generations" ("created_at");
@@ -0,0 +1,13 @@
+create table if not exists "generation_votes" (
+    "generation_id" uuid not null,
+    "user_id" bigint not null,
+    "chat_id" bigint not null,
+    "model" text not null,
+    "prompt_template" text not null,
+    "value" smallint not null,
+    "created_at" timestamp with time zone not null default current_timestamp,
+    "updated_at" timestamp with time zone not null default current_timestamp,
+    primary key (generation_id, user_id)
+);
+
+create index if not exists "generation_votes_model_idx" on "generation_votes" ("model", "prompt_template");
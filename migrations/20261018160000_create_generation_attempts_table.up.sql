@@ -0,0 +1,9 @@
+create table if not exists "generation_attempts" (
+    "id" uuid not null primary key,
+    "model" text not null,
+    "outcome" text not null,
+    "latency_ms" integer not null,
+    "created_at" timestamp with time zone not null default current_timestamp
+);
+
+create index if not exists "generation_attempts_model_idx" on "generation_attempts" ("model", "created_at");
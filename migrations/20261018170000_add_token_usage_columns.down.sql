@@ -0,0 +1,7 @@
+alter table "generation_attempts" drop column if exists "cost";
+alter table "generation_attempts" drop column if exists "completion_tokens";
+alter table "generation_attempts" drop column if exists "prompt_tokens";
+
+alter table "generations" drop column if exists "cost";
+alter table "generations" drop column if exists "completion_tokens";
+alter table "generations" drop column if exists "prompt_tokens";
@@ -0,0 +1,7 @@
+alter table "generations" add column if not exists "prompt_tokens" integer;
+alter table "generations" add column if not exists "completion_tokens" integer;
+alter table "generations" add column if not exists "cost" double precision;
+
+alter table "generation_attempts" add column if not exists "prompt_tokens" integer;
+alter table "generation_attempts" add column if not exists "completion_tokens" integer;
+alter table "generation_attempts" add column if not exists "cost" double precision;
@@ -0,0 +1 @@
+alter table "chat_settings" add column if not exists "replies_enabled" boolean not null default false;
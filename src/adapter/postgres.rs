@@ -3,6 +3,7 @@ use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
 
 const PG_POOL_MAX_CONNECTIONS: u32 = 5;
 
+#[derive(Clone)]
 pub struct PgStore {
     pub pool: Pool<Postgres>,
 }
@@ -20,8 +20,12 @@ pub enum Command {
     Slay,
     #[command(description = "Показать, сколько осталось до нефорской пятницы.")]
     Friday,
-    #[command(description = "Показать, какая модель сгенерировала сообщние (из последних 20)")]
+    #[command(description = "Показать, какая модель сгенерировала сообщение (ответом на него)")]
     Model,
+    #[command(
+        description = "Рейтинг моделей по голосам и надежности.\nНапример, /models 24h, /models 7d или /models 4w"
+    )]
+    Models(String),
     #[command(description = "Отправить стикер или gif с определенным названием.\nНапример, /get xdd",
     aliases = ["get"])]
     GetMedia(String),
@@ -42,6 +46,59 @@ pub enum Command {
 
     #[command(description = "Отмена операции в рамках диалога")]
     Cancel,
+
+    #[command(description = "Подписать чат на автоматический отсчет до нефорской пятницы")]
+    Subscribe,
+
+    #[command(description = "Отписать чат от автоматического отсчета")]
+    Unsubscribe,
+
+    #[command(
+        description = "Показать или изменить настройки пятницы в чате.\nНапример, /settings start 18:00"
+    )]
+    Settings(String),
+
+    #[command(rename = "pin_countdown", description = "Закрепить обновляемый отсчет до нефорской пятницы",
+    aliases = ["pin"])]
+    PinCountdown,
+
+    #[command(rename = "unpin_countdown", description = "Остановить закрепленный отсчет",
+    aliases = ["unpin"])]
+    UnpinCountdown,
+}
+
+#[derive(BotCommands, Clone, Debug, PartialEq)]
+#[command(
+    rename_rule = "lowercase",
+    description = "Команды администратора бота:"
+)]
+pub enum AdminCommand {
+    #[command(description = "Показать состояние моделей и их circuit breaker")]
+    Breakers,
+
+    #[command(description = "Показать шаблоны промптов")]
+    Prompts,
+
+    #[command(
+        rename = "prompt_create",
+        description = "Создать шаблон промпта.\nНапример, /prompt_create офисный Ты офисный менеджер чата {chat_title}..."
+    )]
+    PromptCreate(String),
+
+    #[command(rename = "prompt_edit", description = "Изменить текст шаблона промпта")]
+    PromptEdit(String),
+
+    #[command(
+        rename = "prompt_preview",
+        description = "Показать шаблон с подставленными переменными и пример генерации"
+    )]
+    PromptPreview(String),
+
+    #[command(
+        rename = "prompt_activate",
+        description = "Включить шаблон промпта в этом чате"
+    )]
+    PromptActivate(String),
 }
 
 impl Display for Command {
@@ -51,12 +108,18 @@ impl Display for Command {
             Command::Slay => "/slay",
             Command::Friday => "/friday",
             Command::Model => "/model",
+            Command::Models(_) => "/models",
             Command::GetMedia(_) => "/get",
             Command::ListMedia => "/list",
             Command::AddMedia => "/add",
             Command::RenameMedia => "/rename",
             Command::DeleteMedia => "/delete",
             Command::Cancel => "/cancel",
+            Command::Subscribe => "/subscribe",
+            Command::Unsubscribe => "/unsubscribe",
+            Command::Settings(_) => "/settings",
+            Command::PinCountdown => "/pin",
+            Command::UnpinCountdown => "/unpin",
         })
     }
 }
@@ -70,12 +133,18 @@ impl FromStr for Command {
             "/slay" => Ok(Command::Slay),
             "/friday" => Ok(Command::Friday),
             "/model" => Ok(Command::Model),
+            "/models" => Ok(Command::Models(String::default())),
             "/get" => Ok(Command::GetMedia(String::default())),
             "/delete" => Ok(Command::DeleteMedia),
             "/add" => Ok(Command::AddMedia),
             "/list" => Ok(Command::ListMedia),
             "/rename" => Ok(Command::RenameMedia),
             "/cancel" => Ok(Command::Cancel),
+            "/subscribe" => Ok(Command::Subscribe),
+            "/unsubscribe" => Ok(Command::Unsubscribe),
+            "/settings" => Ok(Command::Settings(String::default())),
+            "/pin" => Ok(Command::PinCountdown),
+            "/unpin" => Ok(Command::UnpinCountdown),
             cmd => Err(CommandConversionError(format!("Unknown command: {}", cmd))),
         }
     }
@@ -4,24 +4,22 @@ use reqwest::Response;
 use std::fmt::Display;
 use tracing::error;
 
-#[derive(Debug, Copy, Clone)]
-pub enum Model {
-    Gigachat,
-    Mistral,
-    Grok,
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Model(String);
+
+impl Model {
+    pub fn new(name: impl Into<String>) -> Self {
+        Model(name.into())
+    }
 }
 
 impl Display for Model {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Model::Gigachat => write!(f, "Gigachat"),
-            Model::Mistral => write!(f, "Mistral"),
-            Model::Grok => write!(f, "Grok"),
-        }
+        f.write_str(&self.0)
     }
 }
 
-pub async fn ensure_success(model: Model, response: Response) -> Result<Response, ApiError> {
+pub async fn ensure_success(model: &Model, response: Response) -> Result<Response, ApiError> {
     if response.status().is_success() {
         return Ok(response);
     }
@@ -32,7 +30,7 @@ pub async fn ensure_success(model: Model, response: Response) -> Result<Response
     error!(%status, %body, %model, "Generation failed");
 
     Err(ApiStatusError {
-        model,
+        model: model.clone(),
         status,
         body,
     })
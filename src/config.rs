@@ -1,23 +1,284 @@
 use crate::errors::BotConfigError;
 use crate::errors::BotConfigError::{
-    BotTokenNotFound, DBURLNotFound, GigaChatClientIDNotFound, GigaChatClientSecretNotFound,
-    LogLevelNotFound, MistralTokenNotFound, ParseLogLevelError, XAITokenNotFound,
+    BotTokenNotFound, DBURLNotFound, LogLevelNotFound, ParseAdminIdsError,
+    ParseHistoryRetentionError, ParseLogLevelError, ParseModelsConfigError, ReadModelsConfigError,
 };
+use crate::generation_controller::budget::{BudgetConfig, PricingConfig};
+use crate::generation_controller::cache::CacheConfig;
+use crate::generation_controller::circuit_breaker::CircuitBreakerConfig;
+use crate::generation_controller::race::RaceConfig;
+use crate::generation_controller::strategy::SelectionStrategy;
+use crate::generation_controller::validation::ValidationConfig;
+use crate::scheduler::schedule::BroadcastSchedule;
 use dotenvy::dotenv;
-use std::env;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
 use std::str::FromStr;
+use std::{env, fs};
+use teloxide::types::UserId;
 use tracing::Level;
 
+const DEFAULT_BROADCAST_DAYS: &str = "mon,tue,wed,thu";
+const DEFAULT_BROADCAST_TIMES: &str = "10:00";
+const DEFAULT_MODELS_CONFIG_PATH: &str = "models.toml";
+const DEFAULT_HISTORY_RETENTION_DAYS: u32 = 90;
+
+const DEFAULT_PROVIDER_WEIGHT: u32 = 1;
+const DEFAULT_PROVIDER_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_PROVIDER_RETRIES: u32 = 1;
+const DEFAULT_BACKOFF_BASE_MS: u64 = 500;
+const DEFAULT_BACKOFF_MAX_MS: u64 = 10_000;
+const DEFAULT_GENERATION_DEADLINE_SECS: u64 = 60;
+const DEFAULT_GIGACHAT_CERT_PATH: &str = "cert.crt";
+const DEFAULT_GIGACHAT_BASE_URL: &str = "https://gigachat.devices.sberbank.ru/api/v1";
+const DEFAULT_GIGACHAT_AUTH_URL: &str = "https://ngw.devices.sberbank.ru:9443/api/v2/oauth";
+const DEFAULT_OLLAMA_HOST: &str = "http://localhost:11434";
+const DEFAULT_CONVERSATION_CONTEXT_MESSAGES: usize = 20;
+const DEFAULT_CONVERSATION_MAX_REPLIES: usize = 3;
+const DEFAULT_CONVERSATION_WINDOW_SECS: u64 = 60;
+
+/// Contents of the models configuration file, see `models.toml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelsConfig {
+    #[serde(default)]
+    pub generation: GenerationConfig,
+    #[serde(default)]
+    pub providers: Vec<ProviderConfig>,
+    #[serde(default)]
+    pub conversation: ConversationConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerationConfig {
+    #[serde(default)]
+    pub strategy: SelectionStrategy,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Overall time budget for one generation across all models and retries.
+    #[serde(default = "default_deadline_secs")]
+    pub deadline_secs: u64,
+    #[serde(default)]
+    pub race: RaceConfig,
+    #[serde(default)]
+    pub validation: ValidationConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        GenerationConfig {
+            strategy: SelectionStrategy::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            deadline_secs: DEFAULT_GENERATION_DEADLINE_SECS,
+            race: RaceConfig::default(),
+            validation: ValidationConfig::default(),
+            cache: CacheConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderConfig {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// How many times a failed request is repeated before giving up on the provider.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Exponential backoff between retries, each delay is randomized up to the current cap.
+    #[serde(default = "default_backoff_base_ms")]
+    pub backoff_base_ms: u64,
+    #[serde(default = "default_backoff_max_ms")]
+    pub backoff_max_ms: u64,
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    /// Once a limit is reached the provider is left out until the next day or month.
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// Tried only after every other provider has failed.
+    #[serde(default)]
+    pub last_resort: bool,
+    #[serde(flatten)]
+    pub kind: ProviderKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ProviderKind {
+    OpenAi(OpenAiProviderConfig),
+    GigaChat(GigaChatProviderConfig),
+    Ollama(OllamaProviderConfig),
+    /// Built-in phrase bank generator, needs neither network nor credentials.
+    Offline,
+}
+
+/// Any server speaking the OpenAI chat completions protocol.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAiProviderConfig {
+    pub base_url: String,
+    pub model: String,
+    /// Name of the environment variable with the API key; local servers usually need none.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Additional fields merged into the request body, e.g. `temperature`.
+    #[serde(default)]
+    pub extra_params: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GigaChatProviderConfig {
+    pub model: String,
+    pub client_id_env: String,
+    pub client_secret_env: String,
+    /// Root certificate of the Russian Trusted CA the GigaChat API is signed with.
+    #[serde(default = "default_gigachat_cert_path")]
+    pub cert_path: String,
+    #[serde(default = "default_gigachat_base_url")]
+    pub base_url: String,
+    #[serde(default = "default_gigachat_auth_url")]
+    pub auth_url: String,
+}
+
+/// Self-hosted model served by Ollama through its native `/api/chat` endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OllamaProviderConfig {
+    pub model: String,
+    #[serde(default = "default_ollama_host")]
+    pub host: String,
+    /// Read the answer as it is generated instead of waiting for the whole text.
+    #[serde(default)]
+    pub stream: bool,
+    /// Model parameters such as `temperature` or `num_ctx`.
+    #[serde(default)]
+    pub options: Map<String, Value>,
+}
+
+/// Answers to mentions and replies, each chat turns them on with `/settings replies on`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConversationConfig {
+    /// How many last messages of the chat the model sees.
+    #[serde(default = "default_context_messages")]
+    pub context_messages: usize,
+    /// At most this many answers per chat within `window_secs`.
+    #[serde(default = "default_max_replies")]
+    pub max_replies: usize,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+}
+
+impl Default for ConversationConfig {
+    fn default() -> Self {
+        ConversationConfig {
+            context_messages: DEFAULT_CONVERSATION_CONTEXT_MESSAGES,
+            max_replies: DEFAULT_CONVERSATION_MAX_REPLIES,
+            window_secs: DEFAULT_CONVERSATION_WINDOW_SECS,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_weight() -> u32 {
+    DEFAULT_PROVIDER_WEIGHT
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_PROVIDER_TIMEOUT_SECS
+}
+
+fn default_retries() -> u32 {
+    DEFAULT_PROVIDER_RETRIES
+}
+
+fn default_backoff_base_ms() -> u64 {
+    DEFAULT_BACKOFF_BASE_MS
+}
+
+fn default_backoff_max_ms() -> u64 {
+    DEFAULT_BACKOFF_MAX_MS
+}
+
+fn default_deadline_secs() -> u64 {
+    DEFAULT_GENERATION_DEADLINE_SECS
+}
+
+fn default_gigachat_cert_path() -> String {
+    DEFAULT_GIGACHAT_CERT_PATH.to_string()
+}
+
+fn default_gigachat_base_url() -> String {
+    DEFAULT_GIGACHAT_BASE_URL.to_string()
+}
+
+fn default_gigachat_auth_url() -> String {
+    DEFAULT_GIGACHAT_AUTH_URL.to_string()
+}
+
+fn default_ollama_host() -> String {
+    DEFAULT_OLLAMA_HOST.to_string()
+}
+
+fn default_context_messages() -> usize {
+    DEFAULT_CONVERSATION_CONTEXT_MESSAGES
+}
+
+fn default_max_replies() -> usize {
+    DEFAULT_CONVERSATION_MAX_REPLIES
+}
+
+fn default_window_secs() -> u64 {
+    DEFAULT_CONVERSATION_WINDOW_SECS
+}
+
+impl ModelsConfig {
+    pub fn from_file(path: &str) -> Result<Self, BotConfigError> {
+        let raw =
+            fs::read_to_string(path).map_err(|e| ReadModelsConfigError(path.to_string(), e))?;
+
+        toml::from_str(&raw).map_err(ParseModelsConfigError)
+    }
+}
+
+/// Telegram users allowed to run admin commands, from comma separated `ADMIN_IDS`.
+#[derive(Debug, Clone, Default)]
+pub struct AdminIds(HashSet<UserId>);
+
+impl AdminIds {
+    pub fn parse(raw: &str) -> Result<Self, BotConfigError> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<u64>()
+                    .map(UserId)
+                    .map_err(|_| ParseAdminIdsError(id.to_string()))
+            })
+            .collect::<Result<HashSet<_>, _>>()
+            .map(AdminIds)
+    }
+
+    pub fn contains(&self, user_id: UserId) -> bool {
+        self.0.contains(&user_id)
+    }
+}
+
 pub struct BotConfig {
     pub tg_token: String,
-    #[allow(unused)]
-    pub gigachat_client_id: String,
-    #[allow(unused)]
-    pub gigachat_client_secret: String,
-    pub mistral_token: String,
-    pub grok_token: String,
+    pub admin_ids: AdminIds,
+    pub models: ModelsConfig,
     pub log_level: Level,
     pub db_conn_str: String,
+    pub broadcast_schedule: BroadcastSchedule,
+    /// Days to keep generation history for /model.
+    pub history_retention_days: u32,
 }
 
 impl BotConfig {
@@ -25,15 +286,11 @@ impl BotConfig {
         dotenv().ok();
         let tg_token = env::var("TELOXIDE_TOKEN").map_err(BotTokenNotFound)?;
 
-        let gigachat_client_id =
-            env::var("GIGACHAT_CLIENT_ID").map_err(GigaChatClientIDNotFound)?;
-
-        let gigachat_client_secret =
-            env::var("GIGACHAT_CLIENT_SECRET").map_err(GigaChatClientSecretNotFound)?;
+        let admin_ids = AdminIds::parse(&env::var("ADMIN_IDS").unwrap_or_default())?;
 
-        let grok_token = env::var("XAI_API_KEY").map_err(XAITokenNotFound)?;
-
-        let mistral_token = env::var("MISTRAL_TOKEN").map_err(MistralTokenNotFound)?;
+        let models_config_path = env::var("MODELS_CONFIG_PATH")
+            .unwrap_or_else(|_| DEFAULT_MODELS_CONFIG_PATH.to_string());
+        let models = ModelsConfig::from_file(&models_config_path)?;
 
         let log_level_str = env::var("LOG_LEVEL").map_err(LogLevelNotFound)?;
 
@@ -42,14 +299,108 @@ impl BotConfig {
 
         let db_conn_str = env::var("DATABASE_URL").map_err(DBURLNotFound)?;
 
+        let broadcast_days =
+            env::var("BROADCAST_DAYS").unwrap_or_else(|_| DEFAULT_BROADCAST_DAYS.to_string());
+        let broadcast_times =
+            env::var("BROADCAST_TIMES").unwrap_or_else(|_| DEFAULT_BROADCAST_TIMES.to_string());
+        let broadcast_schedule = BroadcastSchedule::parse(&broadcast_days, &broadcast_times)?;
+
+        let history_retention_days = match env::var("HISTORY_RETENTION_DAYS") {
+            Ok(raw) => raw
+                .trim()
+                .parse()
+                .map_err(|_| ParseHistoryRetentionError(raw))?,
+            Err(_) => DEFAULT_HISTORY_RETENTION_DAYS,
+        };
+
         Ok(BotConfig {
             tg_token,
-            gigachat_client_id,
-            gigachat_client_secret,
-            mistral_token,
-            grok_token,
+            admin_ids,
+            models,
             log_level,
             db_conn_str,
+            broadcast_schedule,
+            history_retention_days,
         })
     }
 }
+
+#[test]
+fn models_config_parse_test() {
+    let raw = r#"
+        [generation]
+        strategy = "fastest_p50"
+
+        [[providers]]
+        name = "Mistral"
+        kind = "openai"
+        base_url = "https://api.mistral.ai/v1"
+        model = "mistral-small-latest"
+        api_key_env = "MISTRAL_TOKEN"
+        weight = 3
+        extra_params = { temperature = 0.9 }
+        pricing = { prompt_per_million = 0.1, completion_per_million = 0.3 }
+        budget = { daily = 1.5 }
+
+        [[providers]]
+        name = "Gigachat"
+        kind = "gigachat"
+        enabled = false
+        model = "GigaChat-2"
+        client_id_env = "GIGACHAT_CLIENT_ID"
+        client_secret_env = "GIGACHAT_CLIENT_SECRET"
+
+        [[providers]]
+        name = "Local"
+        kind = "ollama"
+        model = "qwen2.5:7b"
+        stream = true
+
+        [[providers]]
+        name = "Offline"
+        kind = "offline"
+        last_resort = true
+    "#;
+
+    let config: ModelsConfig = toml::from_str(raw).unwrap();
+    assert_eq!(config.generation.strategy, SelectionStrategy::FastestP50);
+    assert_eq!(
+        config.generation.deadline_secs,
+        DEFAULT_GENERATION_DEADLINE_SECS
+    );
+    assert_eq!(config.providers.len(), 4);
+    assert_eq!(
+        config.conversation.context_messages,
+        DEFAULT_CONVERSATION_CONTEXT_MESSAGES
+    );
+
+    let mistral = &config.providers[0];
+    assert!(mistral.enabled);
+    assert_eq!(mistral.weight, 3);
+    assert_eq!(mistral.timeout_secs, DEFAULT_PROVIDER_TIMEOUT_SECS);
+    assert_eq!(mistral.backoff_base_ms, DEFAULT_BACKOFF_BASE_MS);
+    assert_eq!(mistral.pricing.completion_per_million, 0.3);
+    assert_eq!(mistral.budget.daily, Some(1.5));
+    assert_eq!(mistral.budget.monthly, None);
+    assert!(
+        matches!(&mistral.kind, ProviderKind::OpenAi(c) if c.extra_params.contains_key("temperature"))
+    );
+
+    let gigachat = &config.providers[1];
+    assert!(!gigachat.enabled);
+    assert!(
+        matches!(&gigachat.kind, ProviderKind::GigaChat(c) if c.cert_path == DEFAULT_GIGACHAT_CERT_PATH && c.base_url == DEFAULT_GIGACHAT_BASE_URL)
+    );
+    assert_eq!(gigachat.pricing, PricingConfig::default());
+    assert_eq!(gigachat.budget, BudgetConfig::default());
+    assert!(!gigachat.last_resort);
+
+    let local = &config.providers[2];
+    assert!(
+        matches!(&local.kind, ProviderKind::Ollama(c) if c.stream && c.host == DEFAULT_OLLAMA_HOST)
+    );
+
+    let offline = &config.providers[3];
+    assert!(offline.last_resort);
+    assert!(matches!(offline.kind, ProviderKind::Offline));
+}
@@ -29,21 +29,33 @@ pub enum ApiError {
     #[error("Certificate parsing error: {0}")]
     CertParseError(#[source] reqwest::Error),
 
-    #[error("Failed to build GigaChatApiClient: {0}")]
+    #[error("Failed to build API client: {0}")]
     ApiClientBuildError(#[source] reqwest::Error),
 
     #[error("Failed to decode response body: {0}")]
     DecodeResponseError(#[source] reqwest::Error),
 
+    #[error("Environment variable '{0}' with API key not found")]
+    ApiKeyNotFound(String, #[source] VarError),
+
     #[error("No content was generated by the model")]
     NoContent,
 
+    #[error("Model {0} reported an error: {1}")]
+    ModelError(Model, String),
+
     #[error("No models were provided")]
     NoModels,
 
     #[error("Failed to generate content with all available models")]
     GenFailed,
 
+    #[error("Generation deadline exceeded")]
+    DeadlineExceeded,
+
+    #[error("Generated text is invalid: {0}")]
+    InvalidOutput(#[from] ValidationError),
+
     #[error("Repo error {0}")]
     StorageError(#[from] RepoError),
 
@@ -88,6 +100,18 @@ pub enum RepoError {
     DBError(#[source] sqlx_core::error::Error),
 }
 
+#[derive(Error, Debug, PartialEq)]
+pub enum ValidationError {
+    #[error("required keyword '{0}' is missing")]
+    MissingKeyword(String),
+
+    #[error("text is {0} characters long, limit is {1}")]
+    TooLong(usize, usize),
+
+    #[error("countdown numbers were changed")]
+    NumbersChanged,
+}
+
 #[derive(Error, Debug)]
 pub enum InfraError {
     #[error("Failed to connect to Postgres {0}")]
@@ -105,21 +129,24 @@ pub enum BotConfigError {
     #[error("Environment variable 'TELOXIDE_TOKEN' not found")]
     BotTokenNotFound(#[source] VarError),
 
-    #[error("Environment variable 'GIGACHAT_CLIENT_ID' not found")]
-    GigaChatClientIDNotFound(#[source] VarError),
+    #[error("Failed to parse ADMIN_IDS: '{0}' is not a valid user id")]
+    ParseAdminIdsError(String),
 
-    #[error("Environment variable 'GIGACHAT_CLIENT_SECRET' not found")]
-    GigaChatClientSecretNotFound(#[source] VarError),
+    #[error("Failed to parse HISTORY_RETENTION_DAYS: '{0}' is not a number of days")]
+    ParseHistoryRetentionError(String),
 
-    #[error("Environment variable 'MISTRAL_TOKEN' not found")]
-    MistralTokenNotFound(#[source] VarError),
+    #[error("Failed to read models config '{0}': {1}")]
+    ReadModelsConfigError(String, #[source] std::io::Error),
 
-    #[error("Environment variable 'XAI_API_KEY' not found")]
-    XAITokenNotFound(#[source] VarError),
+    #[error("Failed to parse models config: {0}")]
+    ParseModelsConfigError(#[source] toml::de::Error),
 
     #[error("Environment variable 'LOG_LEVEL' not found")]
     LogLevelNotFound(#[source] VarError),
 
     #[error("Environment variable 'DATABASE_URL' not found")]
     DBURLNotFound(#[source] VarError),
+
+    #[error("Failed to parse broadcast schedule: '{0}' is not a valid day or time")]
+    ParseBroadcastScheduleError(String),
 }
@@ -0,0 +1,120 @@
+use chrono::Duration;
+
+const SECONDS_PRECISION_THRESHOLD_MINUTES: i64 = 60;
+
+/// Picks the Russian plural form for `n`: 1 день, 2 дня, 5 дней.
+pub fn plural_ru<'a>(n: i64, one: &'a str, few: &'a str, many: &'a str) -> &'a str {
+    let n = n.unsigned_abs();
+
+    match (n % 10, n % 100) {
+        (_, 11..=14) => many,
+        (1, _) => one,
+        (2..=4, _) => few,
+        _ => many,
+    }
+}
+
+/// Formats a countdown like "1 день, 2 часа, 1 минута", skipping zero units.
+/// Seconds are shown only when less than an hour is left.
+pub fn format_time_delta(td: Duration) -> String {
+    format_units(td, td.num_minutes() < SECONDS_PRECISION_THRESHOLD_MINUTES)
+}
+
+/// Same as [`format_time_delta`], but never goes below minutes.
+/// Used for messages that are not updated every second.
+pub fn format_time_delta_minutes(td: Duration) -> String {
+    format_units(td, false)
+}
+
+fn format_units(td: Duration, with_seconds: bool) -> String {
+    let td = td.max(Duration::zero());
+
+    let days = td.num_days();
+    let hours = td.num_hours() % 24;
+    let minutes = td.num_minutes() % 60;
+    let seconds = if with_seconds {
+        td.num_seconds() % 60
+    } else {
+        0
+    };
+
+    let parts: Vec<String> = [
+        (days, ["день", "дня", "дней"]),
+        (hours, ["час", "часа", "часов"]),
+        (minutes, ["минута", "минуты", "минут"]),
+        (seconds, ["секунда", "секунды", "секунд"]),
+    ]
+    .into_iter()
+    .filter(|(value, _)| *value != 0)
+    .map(|(value, [one, few, many])| format!("{} {}", value, plural_ru(value, one, few, many)))
+    .collect();
+
+    if parts.is_empty() {
+        return String::from(if with_seconds {
+            "0 секунд"
+        } else {
+            "0 минут"
+        });
+    }
+
+    parts.join(", ")
+}
+
+#[test]
+fn plural_ru_test() {
+    let forms = |n| plural_ru(n, "день", "дня", "дней");
+
+    assert_eq!(forms(0), "дней");
+    assert_eq!(forms(1), "день");
+    assert_eq!(forms(2), "дня");
+    assert_eq!(forms(4), "дня");
+    assert_eq!(forms(5), "дней");
+    assert_eq!(forms(11), "дней");
+    assert_eq!(forms(12), "дней");
+    assert_eq!(forms(14), "дней");
+    assert_eq!(forms(21), "день");
+    assert_eq!(forms(22), "дня");
+    assert_eq!(forms(25), "дней");
+    assert_eq!(forms(101), "день");
+    assert_eq!(forms(111), "дней");
+    assert_eq!(forms(-1), "день");
+}
+
+#[test]
+fn format_time_delta_test() {
+    let td = Duration::days(1) + Duration::hours(2) + Duration::minutes(1);
+    assert_eq!(format_time_delta(td), "1 день, 2 часа, 1 минута");
+
+    let td = Duration::days(5) + Duration::minutes(21) + Duration::seconds(30);
+    assert_eq!(format_time_delta(td), "5 дней, 21 минута");
+
+    let td = Duration::hours(3);
+    assert_eq!(format_time_delta(td), "3 часа");
+}
+
+#[test]
+fn format_time_delta_seconds_boundary_test() {
+    let td = Duration::minutes(59) + Duration::seconds(59);
+    assert_eq!(format_time_delta(td), "59 минут, 59 секунд");
+
+    let td = Duration::hours(1) + Duration::seconds(59);
+    assert_eq!(format_time_delta(td), "1 час");
+
+    let td = Duration::seconds(12);
+    assert_eq!(format_time_delta(td), "12 секунд");
+
+    let td = Duration::milliseconds(500);
+    assert_eq!(format_time_delta(td), "0 секунд");
+
+    let td = Duration::seconds(-5);
+    assert_eq!(format_time_delta(td), "0 секунд");
+}
+
+#[test]
+fn format_time_delta_minutes_test() {
+    let td = Duration::minutes(42) + Duration::seconds(17);
+    assert_eq!(format_time_delta_minutes(td), "42 минуты");
+
+    let td = Duration::seconds(17);
+    assert_eq!(format_time_delta_minutes(td), "0 минут");
+}
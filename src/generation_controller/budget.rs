@@ -0,0 +1,201 @@
+use crate::common::Model;
+use crate::generation_controller::TokenUsage;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Mutex;
+use tracing::warn;
+
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+/// Price of a million tokens, in whatever currency the provider bills.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PricingConfig {
+    #[serde(default)]
+    pub prompt_per_million: f64,
+    #[serde(default)]
+    pub completion_per_million: f64,
+}
+
+impl PricingConfig {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt_per_million
+            + usage.completion_tokens as f64 * self.completion_per_million)
+            / TOKENS_PER_PRICE_UNIT
+    }
+}
+
+/// Spending limits in the pricing currency, calendar days and months in UTC.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct BudgetConfig {
+    #[serde(default)]
+    pub daily: Option<f64>,
+    #[serde(default)]
+    pub monthly: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetStatus {
+    pub spent_today: f64,
+    pub spent_this_month: f64,
+    pub limits: BudgetConfig,
+    pub exhausted: bool,
+}
+
+#[derive(Debug)]
+struct Spending {
+    day: NaiveDate,
+    daily: f64,
+    monthly: f64,
+}
+
+impl Spending {
+    /// Resets the counters when the day or the month is over.
+    fn roll_over(&mut self, today: NaiveDate) {
+        if self.day == today {
+            return;
+        }
+
+        if (self.day.year(), self.day.month()) != (today.year(), today.month()) {
+            self.monthly = 0.0;
+        }
+
+        self.daily = 0.0;
+        self.day = today;
+    }
+}
+
+/// Tracks what a model has cost so far and takes it out of rotation once a limit is reached.
+#[derive(Debug)]
+pub struct Budget {
+    model: Model,
+    pricing: PricingConfig,
+    limits: BudgetConfig,
+    spent: Mutex<Spending>,
+}
+
+impl Budget {
+    pub fn new(model: Model, pricing: PricingConfig, limits: BudgetConfig) -> Self {
+        Budget {
+            model,
+            pricing,
+            limits,
+            spent: Mutex::new(Spending {
+                day: Utc::now().date_naive(),
+                daily: 0.0,
+                monthly: 0.0,
+            }),
+        }
+    }
+
+    /// Adds the cost of the request and returns it.
+    pub fn charge(&self, usage: &TokenUsage, now: DateTime<Utc>) -> f64 {
+        let cost = self.pricing.cost(usage);
+        let mut spent = self.spent.lock().unwrap();
+        spent.roll_over(now.date_naive());
+
+        let was_exhausted = self.is_over(&spent);
+        spent.daily += cost;
+        spent.monthly += cost;
+
+        if !was_exhausted && self.is_over(&spent) {
+            warn!(
+                model = %self.model,
+                daily = spent.daily,
+                monthly = spent.monthly,
+                "Model is over budget, removing it from rotation"
+            );
+        }
+
+        cost
+    }
+
+    /// Replaces counters with the totals persisted before restart.
+    pub fn restore(&self, daily: f64, monthly: f64, now: DateTime<Utc>) {
+        let mut spent = self.spent.lock().unwrap();
+        spent.day = now.date_naive();
+        spent.daily = daily;
+        spent.monthly = monthly;
+    }
+
+    pub fn is_exhausted(&self, now: DateTime<Utc>) -> bool {
+        let mut spent = self.spent.lock().unwrap();
+        spent.roll_over(now.date_naive());
+        self.is_over(&spent)
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> BudgetStatus {
+        let mut spent = self.spent.lock().unwrap();
+        spent.roll_over(now.date_naive());
+
+        BudgetStatus {
+            spent_today: spent.daily,
+            spent_this_month: spent.monthly,
+            limits: self.limits.clone(),
+            exhausted: self.is_over(&spent),
+        }
+    }
+
+    fn is_over(&self, spent: &Spending) -> bool {
+        self.limits.daily.is_some_and(|limit| spent.daily >= limit)
+            || self
+                .limits
+                .monthly
+                .is_some_and(|limit| spent.monthly >= limit)
+    }
+}
+
+#[test]
+fn pricing_cost_test() {
+    let pricing = PricingConfig {
+        prompt_per_million: 0.1,
+        completion_per_million: 0.3,
+    };
+    let usage = TokenUsage {
+        prompt_tokens: 2_000,
+        completion_tokens: 1_000,
+    };
+
+    assert!((pricing.cost(&usage) - 0.0005).abs() < 1e-12);
+    assert_eq!(PricingConfig::default().cost(&usage), 0.0);
+}
+
+#[test]
+fn budget_limits_test() {
+    use chrono::TimeZone;
+
+    let budget = Budget::new(
+        Model::new("Grok"),
+        PricingConfig {
+            prompt_per_million: 1_000_000.0,
+            completion_per_million: 0.0,
+        },
+        BudgetConfig {
+            daily: Some(2.0),
+            monthly: Some(3.0),
+        },
+    );
+    let usage = TokenUsage {
+        prompt_tokens: 1,
+        completion_tokens: 10,
+    };
+
+    let day = Utc.with_ymd_and_hms(2026, 10, 30, 12, 0, 0).unwrap();
+    budget.restore(0.0, 0.0, day);
+
+    assert_eq!(budget.charge(&usage, day), 1.0);
+    assert!(!budget.is_exhausted(day));
+    budget.charge(&usage, day);
+    assert!(budget.is_exhausted(day));
+
+    let next_day = Utc.with_ymd_and_hms(2026, 10, 31, 12, 0, 0).unwrap();
+    assert!(!budget.is_exhausted(next_day));
+    budget.charge(&usage, next_day);
+    assert!(budget.is_exhausted(next_day));
+    assert_eq!(budget.status(next_day).spent_this_month, 3.0);
+
+    let next_month = Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap();
+    let status = budget.status(next_month);
+    assert!(!status.exhausted);
+    assert_eq!(status.spent_today, 0.0);
+    assert_eq!(status.spent_this_month, 0.0);
+}
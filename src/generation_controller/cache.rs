@@ -0,0 +1,205 @@
+use crate::generation_controller::Generation;
+use dashmap::DashMap;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+const DEFAULT_CACHE_TTL_SECS: u64 = 60;
+const DEFAULT_PREGENERATE_BUFFER: usize = 2;
+const DEFAULT_PREGENERATE_LEAD_MINUTES: u64 = 60;
+
+/// Answers reused for the same request key, so a burst of /friday costs one generation.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CacheConfig {
+    /// How long an answer is given out again, zero disables the cache.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+    #[serde(default)]
+    pub pregenerate: PregenerateConfig,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            ttl_secs: DEFAULT_CACHE_TTL_SECS,
+            pregenerate: PregenerateConfig::default(),
+        }
+    }
+}
+
+/// Texts for the start of Friday prepared in advance, so the announcement doesn't wait for a model.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PregenerateConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Ready texts kept for every subscribed chat.
+    #[serde(default = "default_buffer")]
+    pub buffer: usize,
+    /// How long before the start the buffer is filled.
+    #[serde(default = "default_lead_minutes")]
+    pub lead_minutes: u64,
+}
+
+impl Default for PregenerateConfig {
+    fn default() -> Self {
+        PregenerateConfig {
+            enabled: false,
+            buffer: DEFAULT_PREGENERATE_BUFFER,
+            lead_minutes: DEFAULT_PREGENERATE_LEAD_MINUTES,
+        }
+    }
+}
+
+fn default_ttl_secs() -> u64 {
+    DEFAULT_CACHE_TTL_SECS
+}
+
+fn default_buffer() -> usize {
+    DEFAULT_PREGENERATE_BUFFER
+}
+
+fn default_lead_minutes() -> u64 {
+    DEFAULT_PREGENERATE_LEAD_MINUTES
+}
+
+/// Held while an answer for the key is generated, so a burst waits for one model call.
+pub type Flight = Arc<tokio::sync::Mutex<()>>;
+
+/// Recently given out answers and the pre-generated ones waiting for their key.
+#[derive(Debug)]
+pub struct GenerationCache {
+    ttl: Duration,
+    buffer: usize,
+    recent: Mutex<HashMap<String, (Instant, Generation)>>,
+    ready: Mutex<HashMap<String, VecDeque<Generation>>>,
+    flights: DashMap<String, Flight>,
+}
+
+impl GenerationCache {
+    pub fn new(config: &CacheConfig) -> Self {
+        GenerationCache {
+            ttl: Duration::from_secs(config.ttl_secs),
+            buffer: config.pregenerate.buffer,
+            recent: Mutex::default(),
+            ready: Mutex::default(),
+            flights: DashMap::new(),
+        }
+    }
+
+    /// Recent answer for the key, otherwise a pre-generated one, which becomes the recent one.
+    pub fn get(&self, key: &str, now: Instant) -> Option<Generation> {
+        if let Some((stored_at, generation)) = self.recent.lock().unwrap().get(key)
+            && now.duration_since(*stored_at) < self.ttl
+        {
+            return Some(generation.clone());
+        }
+
+        let generation = self.ready.lock().unwrap().get_mut(key)?.pop_front()?;
+        self.put(key, &generation, now);
+
+        Some(generation)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    pub fn put(&self, key: &str, generation: &Generation, now: Instant) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut recent = self.recent.lock().unwrap();
+        recent.retain(|_, (stored_at, _)| now.duration_since(*stored_at) < self.ttl);
+        recent.insert(key.to_string(), (now, generation.clone()));
+    }
+
+    /// Lock shared by everyone generating for the key, the cache is worth checking again
+    /// once it is taken.
+    pub fn flight(&self, key: &str) -> Flight {
+        self.flights.entry(key.to_string()).or_default().clone()
+    }
+
+    /// Forgets the lock of the key when nobody else waits on it.
+    pub fn land(&self, key: &str, flight: Flight) {
+        drop(flight);
+        self.flights
+            .remove_if(key, |_, flight| Arc::strong_count(flight) == 1);
+    }
+
+    /// How many texts the buffer of the key lacks.
+    pub fn missing(&self, key: &str) -> usize {
+        let ready = self.ready.lock().unwrap();
+        self.buffer
+            .saturating_sub(ready.get(key).map_or(0, VecDeque::len))
+    }
+
+    pub fn push_ready(&self, key: &str, generation: Generation) {
+        self.ready
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .push_back(generation);
+    }
+}
+
+#[test]
+fn generation_cache_test() {
+    use crate::common::Model;
+
+    let generation = |text: &str| Generation {
+        text: text.to_string(),
+        model: Model::new("Grok"),
+        latency: Duration::from_secs(1),
+        usage: None,
+        cost: None,
+    };
+
+    let cache = GenerationCache::new(&CacheConfig::default());
+    let now = Instant::now();
+
+    cache.put("chat:default:1 час", &generation("Час до пятницы"), now);
+    assert_eq!(
+        cache.get("chat:default:1 час", now + Duration::from_secs(30)),
+        Some(generation("Час до пятницы"))
+    );
+    assert_eq!(
+        cache.get("chat:default:1 час", now + Duration::from_secs(60)),
+        None
+    );
+    assert_eq!(cache.get("chat:default:59 минут", now), None);
+
+    assert_eq!(cache.missing("chat:default:started"), 2);
+    cache.push_ready("chat:default:started", generation("Пятница!"));
+    cache.push_ready("chat:default:started", generation("Пятница!!"));
+    assert_eq!(cache.missing("chat:default:started"), 0);
+
+    // The ready text is given out once and then repeated from the recent ones
+    assert_eq!(
+        cache.get("chat:default:started", now),
+        Some(generation("Пятница!"))
+    );
+    assert_eq!(
+        cache.get("chat:default:started", now),
+        Some(generation("Пятница!"))
+    );
+    assert_eq!(cache.missing("chat:default:started"), 1);
+
+    let first = cache.flight("chat:default:1 час");
+    let second = cache.flight("chat:default:1 час");
+    assert!(Arc::ptr_eq(&first, &second));
+    cache.land("chat:default:1 час", first);
+    assert_eq!(cache.flights.len(), 1);
+    cache.land("chat:default:1 час", second);
+    assert!(cache.flights.is_empty());
+
+    let disabled = GenerationCache::new(&CacheConfig {
+        ttl_secs: 0,
+        ..CacheConfig::default()
+    });
+    disabled.put("chat:default:1 час", &generation("Час до пятницы"), now);
+    assert_eq!(disabled.get("chat:default:1 час", now), None);
+}
@@ -0,0 +1,204 @@
+use crate::common::Model;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+const DEFAULT_COOL_DOWN_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures after which the model is skipped.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(default = "default_cool_down_secs")]
+    pub cool_down_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        CircuitBreakerConfig {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cool_down_secs: DEFAULT_COOL_DOWN_SECS,
+        }
+    }
+}
+
+fn default_failure_threshold() -> u32 {
+    DEFAULT_FAILURE_THRESHOLD
+}
+
+fn default_cool_down_secs() -> u64 {
+    DEFAULT_COOL_DOWN_SECS
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl Display for CircuitState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half-open",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BreakerStatus {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Time left until a probe request is allowed, only for the open state.
+    pub retry_in: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Instant,
+    probe_started_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    model: Model,
+    failure_threshold: u32,
+    cool_down: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(model: Model, config: &CircuitBreakerConfig) -> Self {
+        CircuitBreaker {
+            model,
+            failure_threshold: config.failure_threshold.max(1),
+            cool_down: Duration::from_secs(config.cool_down_secs),
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: Instant::now(),
+                probe_started_at: None,
+            }),
+        }
+    }
+
+    /// Checks whether a request may be sent. In the half-open state only a single probe is let
+    /// through; a probe that never reported back (e.g. was cancelled) expires after the cool-down.
+    pub fn try_acquire(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                if inner.opened_at.elapsed() < self.cool_down {
+                    return false;
+                }
+
+                info!(model = %self.model, "Circuit half-open, sending probe request");
+                inner.state = CircuitState::HalfOpen;
+                inner.probe_started_at = Some(Instant::now());
+                true
+            }
+            CircuitState::HalfOpen => {
+                if inner
+                    .probe_started_at
+                    .is_some_and(|started| started.elapsed() < self.cool_down)
+                {
+                    return false;
+                }
+
+                inner.probe_started_at = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.state != CircuitState::Closed {
+            info!(model = %self.model, from = %inner.state, "Circuit closed");
+        }
+
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.probe_started_at = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        inner.probe_started_at = None;
+
+        let should_open = match inner.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => inner.consecutive_failures >= self.failure_threshold,
+            CircuitState::Open => false,
+        };
+
+        if should_open {
+            warn!(
+                model = %self.model,
+                from = %inner.state,
+                failures = inner.consecutive_failures,
+                cool_down = ?self.cool_down,
+                "Circuit opened"
+            );
+            inner.state = CircuitState::Open;
+            inner.opened_at = Instant::now();
+        }
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().unwrap();
+
+        let retry_in = match inner.state {
+            CircuitState::Open => Some(self.cool_down.saturating_sub(inner.opened_at.elapsed())),
+            _ => None,
+        };
+
+        BreakerStatus {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            retry_in,
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn circuit_breaker_transitions_test() {
+    let config = CircuitBreakerConfig {
+        failure_threshold: 2,
+        cool_down_secs: 10,
+    };
+    let breaker = CircuitBreaker::new(Model::new("test"), &config);
+
+    breaker.record_failure();
+    assert!(breaker.try_acquire());
+    breaker.record_failure();
+    assert_eq!(breaker.status().state, CircuitState::Open);
+    assert!(!breaker.try_acquire());
+
+    tokio::time::advance(Duration::from_secs(11)).await;
+    assert!(breaker.try_acquire());
+    assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+    assert!(!breaker.try_acquire());
+
+    breaker.record_failure();
+    assert_eq!(breaker.status().state, CircuitState::Open);
+
+    tokio::time::advance(Duration::from_secs(11)).await;
+    assert!(breaker.try_acquire());
+    breaker.record_success();
+    assert_eq!(breaker.status().state, CircuitState::Closed);
+    assert!(breaker.try_acquire());
+}
@@ -0,0 +1,1024 @@
+pub mod budget;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod race;
+pub mod stats;
+pub mod strategy;
+pub mod validation;
+
+use crate::common::Model;
+use crate::config::GenerationConfig;
+use crate::errors::ApiError;
+use crate::errors::ApiError::{DeadlineExceeded, GenFailed, InvalidOutput, NoModels};
+use crate::generation_controller::budget::{Budget, BudgetConfig, BudgetStatus, PricingConfig};
+use crate::generation_controller::cache::GenerationCache;
+use crate::generation_controller::circuit_breaker::{BreakerStatus, CircuitBreaker};
+use crate::generation_controller::race::RaceConfig;
+use crate::generation_controller::stats::StatsRegistry;
+use crate::generation_controller::strategy::SelectionStrategy;
+use crate::generation_controller::validation::OutputValidator;
+use crate::handlers::root_handler::{ContentGenerator, ModelStatsStore};
+use crate::repo::model_stats_storage_postgres::dto::{
+    AttemptOutcome, GenerationAttempt, ModelLeaderboardEntry, ModelSpending,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveTime, Utc};
+use mockall::automock;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::instrument;
+use tracing::{debug, error, info};
+
+pub type ModelPool = Vec<PooledModel>;
+
+#[derive(Clone)]
+pub struct PooledModel {
+    pub rephraser: Arc<dyn ContentRephraser>,
+    pub weight: u32,
+    pub pricing: PricingConfig,
+    pub budget: BudgetConfig,
+    /// Tried only when every other model has failed, whatever the strategy says.
+    /// Never asked for replies, a canned answer to a conversation is worse than none.
+    pub last_resort: bool,
+}
+
+impl PooledModel {
+    pub fn new(rephraser: Arc<dyn ContentRephraser>, weight: u32) -> Self {
+        PooledModel {
+            rephraser,
+            weight,
+            pricing: PricingConfig::default(),
+            budget: BudgetConfig::default(),
+            last_resort: false,
+        }
+    }
+
+    pub fn with_last_resort(mut self) -> Self {
+        self.last_resort = true;
+        self
+    }
+
+    pub fn with_billing(mut self, pricing: PricingConfig, budget: BudgetConfig) -> Self {
+        self.pricing = pricing;
+        self.budget = budget;
+        self
+    }
+}
+
+/// What the model is asked for, decides how its answer is validated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RequestKind {
+    /// Creative retelling of a countdown or announcement, numbers and keywords must survive.
+    #[default]
+    Rephrase,
+    /// Answer to a chat conversation in the persona voice.
+    Reply,
+}
+
+/// Text to rephrase together with the rendered prompt template it should be rephrased with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationRequest {
+    pub template: String,
+    pub system_prompt: String,
+    pub text: String,
+    pub kind: RequestKind,
+    /// Requests with the same key may share an answer, `None` always asks a model.
+    pub cache_key: Option<String>,
+}
+
+impl GenerationRequest {
+    pub fn new(
+        template: impl Into<String>,
+        system_prompt: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Self {
+        GenerationRequest {
+            template: template.into(),
+            system_prompt: system_prompt.into(),
+            text: text.into(),
+            kind: RequestKind::Rephrase,
+            cache_key: None,
+        }
+    }
+
+    /// Asks for an answer to the conversation in `transcript`, speaking as the persona.
+    pub fn reply(
+        template: impl Into<String>,
+        system_prompt: impl Into<String>,
+        transcript: impl Into<String>,
+    ) -> Self {
+        GenerationRequest {
+            kind: RequestKind::Reply,
+            ..GenerationRequest::new(template, system_prompt, transcript)
+        }
+    }
+
+    pub fn with_cache_key(mut self, key: impl Into<String>) -> Self {
+        self.cache_key = Some(key.into());
+        self
+    }
+}
+
+/// Tokens billed for one request, as reported in the provider `usage` field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+/// Text of the answer received so far, the receiving side shows it while generation goes on.
+pub type TextProgress = watch::Sender<String>;
+
+/// Raw answer of a provider before validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
+}
+
+impl Completion {
+    pub fn new(text: String, usage: Option<TokenUsage>) -> Self {
+        Completion { text, usage }
+    }
+}
+
+impl From<String> for Completion {
+    fn from(text: String) -> Self {
+        Completion::new(text, None)
+    }
+}
+
+/// Accepted answer of the model that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generation {
+    pub text: String,
+    pub model: Model,
+    pub latency: Duration,
+    pub usage: Option<TokenUsage>,
+    /// Price of the request, `None` when the provider didn't report usage.
+    pub cost: Option<f64>,
+}
+
+#[async_trait]
+#[automock]
+pub trait ContentRephraser: Send + Sync {
+    async fn rephrase_text(&self, request: &GenerationRequest) -> Result<Completion, ApiError>;
+
+    /// Publishes the text generated so far to `progress` while the answer is streamed.
+    /// Providers without streaming support publish the whole answer at once.
+    async fn rephrase_text_streaming(
+        &self,
+        request: &GenerationRequest,
+        progress: &TextProgress,
+    ) -> Result<Completion, ApiError> {
+        let completion = self.rephrase_text(request).await?;
+        progress.send_replace(completion.text.clone());
+        Ok(completion)
+    }
+
+    fn get_model_name(&self) -> Model;
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelHealth {
+    pub model: Model,
+    pub breaker: BreakerStatus,
+    pub success_rate: Option<f64>,
+    pub p50_latency: Option<Duration>,
+    pub validation_failures: u64,
+    pub budget: BudgetStatus,
+}
+
+pub struct GenerationController {
+    pub models: ModelPool,
+    strategy: SelectionStrategy,
+    stats: StatsRegistry,
+    breakers: HashMap<Model, CircuitBreaker>,
+    budgets: HashMap<Model, Budget>,
+    turn: AtomicUsize,
+    deadline: Duration,
+    race: RaceConfig,
+    validator: OutputValidator,
+    cache: GenerationCache,
+    stats_store: Option<Arc<dyn ModelStatsStore>>,
+}
+
+impl GenerationController {
+    pub fn new(models: ModelPool, config: GenerationConfig) -> Self {
+        let breakers = models
+            .iter()
+            .map(|m| {
+                let model = m.rephraser.get_model_name();
+                let breaker = CircuitBreaker::new(model.clone(), &config.circuit_breaker);
+                (model, breaker)
+            })
+            .collect();
+
+        let budgets = models
+            .iter()
+            .map(|m| {
+                let model = m.rephraser.get_model_name();
+                let budget = Budget::new(model.clone(), m.pricing.clone(), m.budget.clone());
+                (model, budget)
+            })
+            .collect();
+
+        GenerationController {
+            models,
+            strategy: config.strategy,
+            stats: StatsRegistry::default(),
+            breakers,
+            budgets,
+            turn: AtomicUsize::new(0),
+            deadline: Duration::from_secs(config.deadline_secs),
+            race: config.race,
+            validator: OutputValidator::new(config.validation),
+            cache: GenerationCache::new(&config.cache),
+            stats_store: None,
+        }
+    }
+
+    /// Persists every attempt so the leaderboard survives restarts.
+    pub fn with_stats_store(mut self, store: Arc<dyn ModelStatsStore>) -> Self {
+        self.stats_store = Some(store);
+        self
+    }
+
+    /// Loads what the models have already spent this day and month, so limits hold across restarts.
+    pub async fn restore_budgets(&self) -> Result<(), ApiError> {
+        let Some(store) = &self.stats_store else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        let today = now.date_naive();
+        let day_start = today.and_time(NaiveTime::MIN).and_utc();
+        let month_start = today
+            .with_day(1)
+            .expect("first day exists in every month")
+            .and_time(NaiveTime::MIN)
+            .and_utc();
+
+        let daily = store.spending_since(day_start).await?;
+        let monthly = store.spending_since(month_start).await?;
+
+        for (model, budget) in &self.budgets {
+            let spent = |spending: &[ModelSpending]| {
+                spending
+                    .iter()
+                    .find(|s| s.model == *model)
+                    .map_or(0.0, |s| s.cost)
+            };
+            budget.restore(spent(&daily), spent(&monthly), now);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the model name unless its circuit breaker or budget tells to skip it.
+    fn acquire(&self, pooled: &PooledModel) -> Option<Model> {
+        let model = pooled.rephraser.get_model_name();
+
+        if self
+            .budgets
+            .get(&model)
+            .is_some_and(|b| b.is_exhausted(Utc::now()))
+        {
+            debug!(%model, "model is over budget, skipping it");
+            return None;
+        }
+
+        if self.breakers.get(&model).is_some_and(|b| !b.try_acquire()) {
+            debug!(%model, "circuit is open, skipping model");
+            return None;
+        }
+
+        Some(model)
+    }
+
+    /// Charges the request, validates the model answer and records the outcome. Invalid output
+    /// still means the provider is reachable, so it doesn't count against the circuit breaker.
+    fn finish(
+        &self,
+        model: &Model,
+        request: &GenerationRequest,
+        result: Result<Completion, ApiError>,
+        latency: Duration,
+    ) -> Result<Generation, ApiError> {
+        let usage = result.as_ref().ok().and_then(|c| c.usage);
+        let cost = usage.and_then(|usage| {
+            self.budgets
+                .get(model)
+                .map(|b| b.charge(&usage, Utc::now()))
+        });
+
+        let result = result.and_then(|completion| {
+            match request.kind {
+                RequestKind::Rephrase => self.validator.validate(&request.text, &completion.text),
+                RequestKind::Reply => self.validator.validate_reply(&completion.text),
+            }
+            .map_err(InvalidOutput)
+        });
+        let breaker = self.breakers.get(model);
+
+        match &result {
+            Ok(_) => self.stats.record(model, true, latency),
+            Err(InvalidOutput(_)) => self.stats.record_validation_failure(model, latency),
+            Err(_) => self.stats.record(model, false, latency),
+        }
+
+        if let Some(breaker) = breaker {
+            match &result {
+                Ok(_) | Err(InvalidOutput(_)) => breaker.record_success(),
+                Err(_) => breaker.record_failure(),
+            }
+        }
+
+        if let Some(store) = self.stats_store.clone() {
+            let outcome = match &result {
+                Ok(_) => AttemptOutcome::Success,
+                Err(InvalidOutput(_)) => AttemptOutcome::Invalid,
+                Err(_) => AttemptOutcome::Failure,
+            };
+            let attempt = GenerationAttempt::new(model.clone(), outcome, latency, usage, cost);
+
+            tokio::spawn(async move {
+                if let Err(e) = store.record_attempt(&attempt).await {
+                    error!(error = %e, "Failed to save generation attempt");
+                }
+            });
+        }
+
+        result.map(|text| Generation {
+            text,
+            model: model.clone(),
+            latency,
+            usage,
+            cost,
+        })
+    }
+}
+
+impl GenerationController {
+    /// Gives out a cached answer when there is one, regenerations always ask a model.
+    async fn generate(
+        &self,
+        request: &GenerationRequest,
+        excluded: Option<&Model>,
+        progress: Option<&TextProgress>,
+    ) -> Result<Generation, ApiError> {
+        let key = request.cache_key.as_deref().filter(|_| excluded.is_none());
+        let Some(key) = key else {
+            return self.generate_uncached(request, excluded, progress).await;
+        };
+
+        if let Some(generation) = self.cached(key, progress) {
+            return Ok(generation);
+        }
+
+        if !self.cache.is_enabled() {
+            return self.generate_uncached(request, None, progress).await;
+        }
+
+        // Requests arriving while the answer is generated wait for it instead of asking again
+        let flight = self.cache.flight(key);
+        let result = {
+            let _turn = flight.lock().await;
+            match self.cached(key, progress) {
+                Some(generation) => Ok(generation),
+                None => {
+                    let result = self.generate_uncached(request, None, progress).await;
+                    if let Ok(generation) = &result {
+                        self.cache.put(key, generation, Instant::now());
+                    }
+                    result
+                }
+            }
+        };
+        self.cache.land(key, flight);
+
+        result
+    }
+
+    fn cached(&self, key: &str, progress: Option<&TextProgress>) -> Option<Generation> {
+        let generation = self.cache.get(key, Instant::now())?;
+        info!(key, model = %generation.model, "Giving out cached generation");
+        if let Some(progress) = progress {
+            progress.send_replace(generation.text.clone());
+        }
+
+        Some(generation)
+    }
+
+    /// Tries models in strategy order; `excluded` is skipped unless it is the only option.
+    /// With `progress` the answer is streamed, a failed model's partial text is cleared
+    /// before the next one starts. Racing models never stream.
+    #[instrument(skip(self, request), fields(template = %request.template), err)]
+    async fn generate_uncached(
+        &self,
+        request: &GenerationRequest,
+        excluded: Option<&Model>,
+        progress: Option<&TextProgress>,
+    ) -> Result<Generation, ApiError> {
+        if self.models.is_empty() {
+            error!("no models were provided");
+            return Err(NoModels);
+        }
+
+        let turn = self.turn.fetch_add(1, Ordering::Relaxed);
+        let deadline = Instant::now() + self.deadline;
+        let mut candidates = self.strategy.order(&self.models, &self.stats, turn);
+
+        if request.kind == RequestKind::Reply {
+            candidates.retain(|m| !m.last_resort);
+        }
+
+        if let Some(excluded) = excluded {
+            let others: Vec<&PooledModel> = candidates
+                .iter()
+                .copied()
+                .filter(|m| m.rephraser.get_model_name() != *excluded)
+                .collect();
+
+            if !others.is_empty() {
+                candidates = others;
+            }
+        }
+
+        if self.race.enabled {
+            return self.race(request, candidates, deadline).await;
+        }
+
+        for sh in candidates {
+            if Instant::now() >= deadline {
+                error!("Generation deadline exceeded");
+                return Err(DeadlineExceeded);
+            }
+
+            let Some(model) = self.acquire(sh) else {
+                continue;
+            };
+
+            let started_at = Instant::now();
+            let completion = async {
+                match progress {
+                    Some(progress) => {
+                        progress.send_replace(String::new());
+                        sh.rephraser
+                            .rephrase_text_streaming(request, progress)
+                            .await
+                    }
+                    None => sh.rephraser.rephrase_text(request).await,
+                }
+            };
+            let result = tokio::time::timeout_at(deadline, completion)
+                .await
+                .unwrap_or(Err(DeadlineExceeded));
+            let latency = started_at.elapsed();
+            match self.finish(&model, request, result, latency) {
+                Ok(generation) => return Ok(generation),
+
+                Err(err) => {
+                    error!(error = %err, "failed to generated content, trying next model");
+                    if let Some(progress) = progress {
+                        progress.send_replace(String::new());
+                    }
+                    continue;
+                }
+            }
+        }
+
+        error!("Generation with all models has failed");
+        Err(GenFailed)
+    }
+}
+
+#[async_trait]
+impl ContentGenerator for GenerationController {
+    async fn generate_text(&self, request: &GenerationRequest) -> Result<Generation, ApiError> {
+        self.generate(request, None, None).await
+    }
+
+    async fn regenerate_text(
+        &self,
+        request: &GenerationRequest,
+        previous: &Model,
+    ) -> Result<Generation, ApiError> {
+        self.generate(request, Some(previous), None).await
+    }
+
+    async fn generate_streaming(
+        &self,
+        request: &GenerationRequest,
+        previous: Option<&Model>,
+        progress: &TextProgress,
+    ) -> Result<Generation, ApiError> {
+        self.generate(request, previous, Some(progress)).await
+    }
+
+    async fn pregenerate(&self, request: &GenerationRequest) -> Result<usize, ApiError> {
+        let Some(key) = &request.cache_key else {
+            return Ok(0);
+        };
+
+        let missing = self.cache.missing(key);
+        for _ in 0..missing {
+            let generation = self.generate_uncached(request, None, None).await?;
+            self.cache.push_ready(key, generation);
+        }
+
+        Ok(missing)
+    }
+
+    fn model_health(&self) -> Vec<ModelHealth> {
+        self.models
+            .iter()
+            .filter_map(|m| {
+                let model = m.rephraser.get_model_name();
+                let breaker = self.breakers.get(&model)?.status();
+
+                Some(ModelHealth {
+                    success_rate: self.stats.success_rate(&model),
+                    p50_latency: self.stats.p50_latency(&model),
+                    validation_failures: self.stats.validation_failures(&model),
+                    budget: self.budgets.get(&model)?.status(Utc::now()),
+                    breaker,
+                    model,
+                })
+            })
+            .collect()
+    }
+
+    async fn leaderboard(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ModelLeaderboardEntry>, ApiError> {
+        let mut persisted = match &self.stats_store {
+            Some(store) => store.leaderboard(since).await?,
+            None => Vec::new(),
+        };
+
+        let mut entries: Vec<ModelLeaderboardEntry> = self
+            .models
+            .iter()
+            .map(|m| {
+                let model = m.rephraser.get_model_name();
+                match persisted.iter().position(|e| e.model == model) {
+                    Some(idx) => persisted.swap_remove(idx),
+                    None => ModelLeaderboardEntry::empty(model),
+                }
+            })
+            .collect();
+
+        // Models removed from the pool may still have votes worth showing.
+        entries.extend(persisted);
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+pub fn test_request(text: &str) -> GenerationRequest {
+    GenerationRequest::new("test", "test prompt", text)
+}
+
+#[tokio::test]
+async fn generation_controller_fails_test() {
+    let controller = GenerationController::new(vec![], GenerationConfig::default());
+    let res = controller
+        .generate_text(&test_request("some test text"))
+        .await;
+
+    assert!(matches!(res, Err(NoModels)))
+}
+
+#[tokio::test]
+async fn generation_controller_fallback_test() {
+    let mut failing = MockContentRephraser::new();
+    failing
+        .expect_rephrase_text()
+        .returning(|_| Box::pin(async { Err(GenFailed) }));
+
+    failing
+        .expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut succeeding = MockContentRephraser::new();
+    succeeding.expect_rephrase_text().returning(|_| {
+        Box::pin(async { Ok("Нефорская пятница близко".to_string().into()) })
+    });
+
+    succeeding
+        .expect_get_model_name()
+        .return_const(Model::new("Mistral"));
+
+    let controller = GenerationController::new(
+        vec![
+            PooledModel::new(Arc::new(failing), 1),
+            PooledModel::new(Arc::new(succeeding), 1),
+        ],
+        GenerationConfig::default(),
+    );
+
+    let res = controller
+        .generate_text(&test_request("some test text"))
+        .await;
+
+    assert!(res.is_ok());
+
+    let Generation { text, model, .. } = res.unwrap();
+    assert!(matches!(text.as_str(), "Нефорская пятница близко"));
+    assert_eq!(model, Model::new("Mistral"));
+}
+
+#[tokio::test]
+async fn generation_controller_failed_test() {
+    let mut failing = MockContentRephraser::new();
+    failing
+        .expect_rephrase_text()
+        .returning(|_| Box::pin(async { Err(GenFailed) }));
+
+    failing
+        .expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let controller = GenerationController::new(
+        vec![PooledModel::new(Arc::new(failing), 1)],
+        GenerationConfig::default(),
+    );
+
+    let res = controller
+        .generate_text(&test_request("some test text"))
+        .await;
+
+    assert!(matches!(res, Err(GenFailed)));
+}
+
+#[tokio::test]
+async fn generation_controller_circuit_breaker_test() {
+    let mut failing = MockContentRephraser::new();
+    failing
+        .expect_rephrase_text()
+        .times(2)
+        .returning(|_| Box::pin(async { Err(GenFailed) }));
+
+    failing
+        .expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut config = GenerationConfig::default();
+    config.circuit_breaker.failure_threshold = 2;
+
+    let controller =
+        GenerationController::new(vec![PooledModel::new(Arc::new(failing), 1)], config);
+
+    for _ in 0..3 {
+        let res = controller
+            .generate_text(&test_request("some test text"))
+            .await;
+        assert!(matches!(res, Err(GenFailed)));
+    }
+
+    let health = controller.model_health();
+    assert_eq!(health[0].breaker.state, circuit_breaker::CircuitState::Open);
+}
+
+#[tokio::test(start_paused = true)]
+async fn generation_controller_deadline_test() {
+    let mut slow = MockContentRephraser::new();
+    slow.expect_rephrase_text().times(1).returning(|_| {
+        Box::pin(async {
+            tokio::time::sleep(Duration::from_secs(120)).await;
+            Ok("Нефорская пятница опоздала".to_string().into())
+        })
+    });
+
+    slow.expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut never_called = MockContentRephraser::new();
+    never_called.expect_rephrase_text().times(0);
+    never_called
+        .expect_get_model_name()
+        .return_const(Model::new("Mistral"));
+
+    let config = GenerationConfig {
+        strategy: SelectionStrategy::Priority,
+        ..GenerationConfig::default()
+    };
+
+    let controller = GenerationController::new(
+        vec![
+            PooledModel::new(Arc::new(slow), 1),
+            PooledModel::new(Arc::new(never_called), 1),
+        ],
+        config,
+    );
+
+    let res = controller
+        .generate_text(&test_request("some test text"))
+        .await;
+
+    assert!(matches!(res, Err(DeadlineExceeded)));
+}
+
+#[tokio::test]
+async fn generation_controller_validation_test() {
+    let mut invalid = MockContentRephraser::new();
+    invalid
+        .expect_rephrase_text()
+        .returning(|_| Box::pin(async { Ok("Скоро выходные".to_string().into()) }));
+
+    invalid
+        .expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut valid = MockContentRephraser::new();
+    valid.expect_rephrase_text().returning(|_| {
+        Box::pin(async {
+            Ok("**Нефорская пятница** через 3 часа".to_string().into())
+        })
+    });
+
+    valid
+        .expect_get_model_name()
+        .return_const(Model::new("Mistral"));
+
+    let config = GenerationConfig {
+        strategy: SelectionStrategy::Priority,
+        ..GenerationConfig::default()
+    };
+
+    let controller = GenerationController::new(
+        vec![
+            PooledModel::new(Arc::new(invalid), 1),
+            PooledModel::new(Arc::new(valid), 1),
+        ],
+        config,
+    );
+
+    let Generation { text, model, .. } = controller
+        .generate_text(&test_request("До нефорской пятницы осталось: 3 часа"))
+        .await
+        .unwrap();
+
+    assert_eq!(text, "Нефорская пятница через 3 часа");
+    assert_eq!(model, Model::new("Mistral"));
+
+    let health = controller.model_health();
+    assert_eq!(health[0].validation_failures, 1);
+    assert_eq!(health[0].breaker.consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn generation_controller_reply_skips_last_resort_test() {
+    use crate::offline_api::api::OfflineRephraser;
+
+    let controller = GenerationController::new(
+        vec![PooledModel::new(Arc::new(OfflineRephraser::new("Offline")), 1).with_last_resort()],
+        GenerationConfig::default(),
+    );
+
+    // The offline model would only echo the chat back, so the reply fails instead
+    let reply = GenerationRequest::reply("test", "test prompt", "Ира: @bot привет");
+    assert!(matches!(
+        controller.generate_text(&reply).await,
+        Err(GenFailed)
+    ));
+
+    let rephrase = test_request("ЭТО НЕФОРСКАЯ ПЯТНИЦА, ДЕТКА!");
+    let generation = controller.generate_text(&rephrase).await.unwrap();
+    assert_eq!(generation.model, Model::new("Offline"));
+}
+
+#[tokio::test]
+async fn generation_controller_regenerate_test() {
+    let mut grok = MockContentRephraser::new();
+    grok.expect_rephrase_text().returning(|_| {
+        Box::pin(async { Ok("Нефорская пятница от Grok".to_string().into()) })
+    });
+    grok.expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut mistral = MockContentRephraser::new();
+    mistral.expect_rephrase_text().returning(|_| {
+        Box::pin(async { Ok("Нефорская пятница от Mistral".to_string().into()) })
+    });
+    mistral
+        .expect_get_model_name()
+        .return_const(Model::new("Mistral"));
+
+    let config = GenerationConfig {
+        strategy: SelectionStrategy::Priority,
+        ..GenerationConfig::default()
+    };
+
+    let controller = GenerationController::new(
+        vec![
+            PooledModel::new(Arc::new(grok), 1),
+            PooledModel::new(Arc::new(mistral), 1),
+        ],
+        config,
+    );
+
+    let request = test_request("some test text");
+
+    let generation = controller
+        .regenerate_text(&request, &Model::new("Grok"))
+        .await
+        .unwrap();
+    assert_eq!(generation.model, Model::new("Mistral"));
+
+    let generation = controller
+        .regenerate_text(&request, &Model::new("Gigachat"))
+        .await
+        .unwrap();
+    assert_eq!(generation.model, Model::new("Grok"));
+}
+
+#[tokio::test]
+async fn generation_controller_streaming_test() {
+    let mut failing = MockContentRephraser::new();
+    failing
+        .expect_rephrase_text_streaming()
+        .returning(|_, progress| {
+            progress.send_replace("Нефорская".to_string());
+            Box::pin(async { Err(GenFailed) })
+        });
+    failing
+        .expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut succeeding = MockContentRephraser::new();
+    succeeding
+        .expect_rephrase_text_streaming()
+        .returning(|_, progress| {
+            progress.send_modify(|text| text.push_str("Нефорская пятница близко"));
+            Box::pin(async {
+                Ok("Нефорская пятница близко".to_string().into())
+            })
+        });
+    succeeding
+        .expect_get_model_name()
+        .return_const(Model::new("Mistral"));
+
+    let config = GenerationConfig {
+        strategy: SelectionStrategy::Priority,
+        ..GenerationConfig::default()
+    };
+
+    let controller = GenerationController::new(
+        vec![
+            PooledModel::new(Arc::new(failing), 1),
+            PooledModel::new(Arc::new(succeeding), 1),
+        ],
+        config,
+    );
+
+    let (progress, shown) = watch::channel(String::new());
+    let generation = controller
+        .generate_streaming(&test_request("some test text"), None, &progress)
+        .await
+        .unwrap();
+
+    // The failed model's partial text is dropped before the next attempt
+    assert_eq!(generation.model, Model::new("Mistral"));
+    assert_eq!(*shown.borrow(), "Нефорская пятница близко");
+}
+
+#[tokio::test]
+async fn generation_controller_cache_test() {
+    let mut grok = MockContentRephraser::new();
+    grok.expect_rephrase_text().times(4).returning(|_| {
+        Box::pin(async { Ok("Нефорская пятница близко".to_string().into()) })
+    });
+    grok.expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let controller = GenerationController::new(
+        vec![PooledModel::new(Arc::new(grok), 1)],
+        GenerationConfig::default(),
+    );
+
+    let request = test_request("some test text").with_cache_key("chat:test:1 час");
+
+    // One model call for the first request, the second one is answered from the cache
+    let first = controller.generate_text(&request).await.unwrap();
+    let second = controller.generate_text(&request).await.unwrap();
+    assert_eq!(first, second);
+    assert_eq!(second.model, Model::new("Grok"));
+
+    // Regeneration asks the model even for a cached key
+    controller
+        .regenerate_text(&request, &Model::new("Grok"))
+        .await
+        .unwrap();
+
+    // The buffer is filled once, the ready text is given out without a model call
+    let started = test_request("some test text").with_cache_key("chat:test:started");
+    assert_eq!(controller.pregenerate(&started).await.unwrap(), 2);
+    assert_eq!(controller.pregenerate(&started).await.unwrap(), 0);
+    assert_eq!(
+        controller.generate_text(&started).await.unwrap().model,
+        Model::new("Grok")
+    );
+}
+
+#[tokio::test]
+async fn generation_controller_cache_single_flight_test() {
+    let mut grok = MockContentRephraser::new();
+    grok.expect_rephrase_text().times(1).returning(|_| {
+        Box::pin(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok("Нефорская пятница близко".to_string().into())
+        })
+    });
+    grok.expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let controller = GenerationController::new(
+        vec![PooledModel::new(Arc::new(grok), 1)],
+        GenerationConfig::default(),
+    );
+
+    let request = test_request("some test text").with_cache_key("chat:test:1 час");
+    let (first, second) = tokio::join!(
+        controller.generate_text(&request),
+        controller.generate_text(&request)
+    );
+
+    assert_eq!(first.unwrap(), second.unwrap());
+}
+
+#[tokio::test]
+async fn generation_controller_budget_test() {
+    let mut expensive = MockContentRephraser::new();
+    expensive.expect_rephrase_text().times(1).returning(|_| {
+        Box::pin(async {
+            Ok(Completion::new(
+                "Нефорская пятница за деньги".to_string(),
+                Some(TokenUsage {
+                    prompt_tokens: 1_000,
+                    completion_tokens: 500,
+                }),
+            ))
+        })
+    });
+    expensive
+        .expect_get_model_name()
+        .return_const(Model::new("Grok"));
+
+    let mut free = MockContentRephraser::new();
+    free.expect_rephrase_text().returning(|_| {
+        Box::pin(async {
+            Ok("Бесплатная нефорская пятница".to_string().into())
+        })
+    });
+    free.expect_get_model_name()
+        .return_const(Model::new("Ollama"));
+
+    let config = GenerationConfig {
+        strategy: SelectionStrategy::Priority,
+        ..GenerationConfig::default()
+    };
+
+    let controller = GenerationController::new(
+        vec![
+            PooledModel::new(Arc::new(expensive), 1).with_billing(
+                PricingConfig {
+                    prompt_per_million: 1_000.0,
+                    completion_per_million: 2_000.0,
+                },
+                BudgetConfig {
+                    daily: Some(1.0),
+                    monthly: None,
+                },
+            ),
+            PooledModel::new(Arc::new(free), 1),
+        ],
+        config,
+    );
+
+    let generation = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(generation.model, Model::new("Grok"));
+    assert_eq!(generation.cost, Some(2.0));
+
+    let generation = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(generation.model, Model::new("Ollama"));
+    assert_eq!(generation.cost, None);
+
+    let health = controller.model_health();
+    assert!(health[0].budget.exhausted);
+    assert_eq!(health[0].budget.spent_today, 2.0);
+}
@@ -0,0 +1,283 @@
+use crate::common::Model;
+use crate::errors::ApiError;
+use crate::errors::ApiError::{DeadlineExceeded, GenFailed};
+use crate::generation_controller::{
+    Completion, Generation, GenerationController, GenerationRequest, PooledModel,
+};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::{Instant, sleep_until};
+use tracing::{debug, error, info};
+
+const DEFAULT_FAN_OUT: usize = 2;
+const DEFAULT_HEDGE_DELAY_MS: u64 = 0;
+
+/// Hedged generation: several models are asked at once and the first answer wins.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RaceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many requests may be in flight at the same time.
+    #[serde(default = "default_fan_out")]
+    pub fan_out: usize,
+    /// Delay before starting each next request, zero fires them all at once.
+    #[serde(default = "default_hedge_delay_ms")]
+    pub hedge_delay_ms: u64,
+}
+
+impl Default for RaceConfig {
+    fn default() -> Self {
+        RaceConfig {
+            enabled: false,
+            fan_out: DEFAULT_FAN_OUT,
+            hedge_delay_ms: DEFAULT_HEDGE_DELAY_MS,
+        }
+    }
+}
+
+fn default_fan_out() -> usize {
+    DEFAULT_FAN_OUT
+}
+
+fn default_hedge_delay_ms() -> u64 {
+    DEFAULT_HEDGE_DELAY_MS
+}
+
+type RaceResult = (Model, Result<Completion, ApiError>, Duration);
+
+impl GenerationController {
+    /// Keeps up to `fan_out` requests running, starting them `hedge_delay_ms` apart,
+    /// and replaces failed ones with the next candidate. Requests still running when
+    /// the first answer arrives are aborted together with the join set.
+    pub(super) async fn race(
+        &self,
+        request: &GenerationRequest,
+        candidates: Vec<&PooledModel>,
+        deadline: Instant,
+    ) -> Result<Generation, ApiError> {
+        let fan_out = self.race.fan_out.max(1);
+        let hedge_delay = Duration::from_millis(self.race.hedge_delay_ms);
+
+        let mut pending = candidates.into_iter().peekable();
+        let mut running: JoinSet<RaceResult> = JoinSet::new();
+        let mut next_launch = Instant::now();
+
+        loop {
+            // Last resort models are never raced against others, only started once all are done
+            let can_launch = running.len() < fan_out
+                && pending
+                    .peek()
+                    .is_some_and(|m| !m.last_resort || running.is_empty());
+
+            if can_launch && (running.is_empty() || Instant::now() >= next_launch) {
+                let pooled = pending.next().expect("peeked above");
+                if let Some(model) = self.acquire(pooled) {
+                    debug!(%model, "starting racing request");
+
+                    let rephraser = pooled.rephraser.clone();
+                    let request = request.clone();
+                    running.spawn(async move {
+                        let started_at = Instant::now();
+                        let result = rephraser.rephrase_text(&request).await;
+                        (model, result, started_at.elapsed())
+                    });
+                    next_launch = Instant::now() + hedge_delay;
+                }
+                continue;
+            }
+
+            if running.is_empty() {
+                error!("Generation with all models has failed");
+                return Err(GenFailed);
+            }
+
+            tokio::select! {
+                joined = running.join_next() => {
+                    let Some(joined) = joined else { continue };
+
+                    match joined {
+                        Ok((model, result, latency)) => {
+                            match self.finish(&model, request, result, latency) {
+                                Ok(generation) => {
+                                    info!(%model, remaining = running.len(), "model won the race");
+                                    return Ok(generation);
+                                }
+                                Err(err) => error!(error = %err, %model, "racing model failed"),
+                            }
+                        }
+                        Err(err) => {
+                            error!(error = %err, "racing request panicked");
+                        }
+                    }
+
+                    next_launch = Instant::now();
+                }
+
+                _ = sleep_until(next_launch), if can_launch => {}
+
+                _ = sleep_until(deadline) => {
+                    error!("Generation deadline exceeded");
+                    return Err(DeadlineExceeded);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn delayed_model(name: &str, delay_secs: u64, result: Result<&'static str, ()>) -> PooledModel {
+    use crate::generation_controller::MockContentRephraser;
+    use std::sync::Arc;
+
+    let mut rephraser = MockContentRephraser::new();
+    rephraser.expect_rephrase_text().returning(move |_| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(delay_secs)).await;
+            result
+                .map(|text| Completion::from(text.to_string()))
+                .map_err(|_| GenFailed)
+        })
+    });
+    rephraser
+        .expect_get_model_name()
+        .return_const(Model::new(name));
+
+    PooledModel::new(Arc::new(rephraser), 1)
+}
+
+#[cfg(test)]
+fn race_controller(models: Vec<PooledModel>, hedge_delay_ms: u64) -> GenerationController {
+    use crate::config::GenerationConfig;
+    use crate::generation_controller::strategy::SelectionStrategy;
+
+    GenerationController::new(
+        models,
+        GenerationConfig {
+            strategy: SelectionStrategy::Priority,
+            race: RaceConfig {
+                enabled: true,
+                fan_out: 2,
+                hedge_delay_ms,
+            },
+            ..GenerationConfig::default()
+        },
+    )
+}
+
+#[tokio::test(start_paused = true)]
+async fn race_fastest_wins_test() {
+    use crate::generation_controller::test_request;
+    use crate::handlers::root_handler::ContentGenerator;
+
+    let controller = race_controller(
+        vec![
+            delayed_model("Grok", 10, Ok("Медленная нефорская пятница")),
+            delayed_model("Mistral", 1, Ok("Быстрая нефорская пятница")),
+        ],
+        0,
+    );
+
+    let Generation { text, model, .. } = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(text, "Быстрая нефорская пятница");
+    assert_eq!(model, Model::new("Mistral"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn race_replaces_failed_model_test() {
+    use crate::generation_controller::test_request;
+    use crate::handlers::root_handler::ContentGenerator;
+
+    let controller = race_controller(
+        vec![
+            delayed_model("Grok", 1, Err(())),
+            delayed_model("Mistral", 30, Ok("Медленная нефорская пятница")),
+            delayed_model("Gigachat", 2, Ok("Третья нефорская пятница")),
+        ],
+        0,
+    );
+
+    let Generation { text, model, .. } = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(text, "Третья нефорская пятница");
+    assert_eq!(model, Model::new("Gigachat"));
+
+    let failing = race_controller(vec![delayed_model("Grok", 1, Err(()))], 0);
+    assert!(matches!(
+        failing.generate_text(&test_request("some test text")).await,
+        Err(GenFailed)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn race_hedge_delay_test() {
+    use crate::generation_controller::test_request;
+    use crate::handlers::root_handler::ContentGenerator;
+
+    let controller = race_controller(
+        vec![
+            delayed_model("Grok", 3, Ok("Первая нефорская пятница")),
+            delayed_model("Mistral", 1, Ok("Запасная нефорская пятница")),
+        ],
+        5_000,
+    );
+
+    let Generation { model, .. } = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(model, Model::new("Grok"));
+
+    let controller = race_controller(
+        vec![
+            delayed_model("Grok", 10, Ok("Первая нефорская пятница")),
+            delayed_model("Mistral", 1, Ok("Запасная нефорская пятница")),
+        ],
+        5_000,
+    );
+
+    let Generation { model, .. } = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(model, Model::new("Mistral"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn race_last_resort_test() {
+    use crate::generation_controller::test_request;
+    use crate::handlers::root_handler::ContentGenerator;
+
+    let controller = race_controller(
+        vec![
+            delayed_model("Grok", 5, Ok("Медленная нефорская пятница")),
+            delayed_model("Offline", 0, Ok("Офлайн нефорская пятница")).with_last_resort(),
+        ],
+        0,
+    );
+
+    let Generation { model, .. } = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(model, Model::new("Grok"));
+
+    let controller = race_controller(
+        vec![
+            delayed_model("Grok", 1, Err(())),
+            delayed_model("Offline", 0, Ok("Офлайн нефорская пятница")).with_last_resort(),
+        ],
+        0,
+    );
+
+    let Generation { model, .. } = controller
+        .generate_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(model, Model::new("Offline"));
+}
@@ -0,0 +1,170 @@
+use crate::common::Model;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const STATS_WINDOW_SIZE: usize = 50;
+/// Older attempts are forgotten, so a demoted model is ranked healthy again after a while.
+const STATS_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+const MIN_SAMPLES_FOR_HEALTH: usize = 5;
+const UNHEALTHY_SUCCESS_RATE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy)]
+struct Attempt {
+    success: bool,
+    latency: Duration,
+    at: Instant,
+}
+
+/// Sliding window over the most recent generation attempts of one model, up to `STATS_MAX_AGE` old.
+#[derive(Debug, Default)]
+pub struct ModelStats {
+    attempts: VecDeque<Attempt>,
+    validation_failures: u64,
+}
+
+impl ModelStats {
+    pub fn record(&mut self, success: bool, latency: Duration) {
+        while self.attempts.len() == STATS_WINDOW_SIZE
+            || self.attempts.front().is_some_and(|a| !a.is_recent())
+        {
+            self.attempts.pop_front();
+        }
+
+        self.attempts.push_back(Attempt {
+            success,
+            latency,
+            at: Instant::now(),
+        });
+    }
+
+    fn recent(&self) -> impl Iterator<Item = &Attempt> {
+        self.attempts.iter().filter(|a| a.is_recent())
+    }
+
+    /// Invalid output counts as a failed attempt and is also tracked separately.
+    pub fn record_validation_failure(&mut self, latency: Duration) {
+        self.record(false, latency);
+        self.validation_failures += 1;
+    }
+
+    pub fn validation_failures(&self) -> u64 {
+        self.validation_failures
+    }
+
+    pub fn success_rate(&self) -> Option<f64> {
+        let (attempts, successes) = self
+            .recent()
+            .fold((0, 0), |(all, ok), a| (all + 1, ok + a.success as usize));
+        if attempts == 0 {
+            return None;
+        }
+
+        Some(successes as f64 / attempts as f64)
+    }
+
+    pub fn p50_latency(&self) -> Option<Duration> {
+        let mut latencies: Vec<Duration> = self
+            .recent()
+            .filter(|a| a.success)
+            .map(|a| a.latency)
+            .collect();
+
+        if latencies.is_empty() {
+            return None;
+        }
+
+        latencies.sort();
+        Some(latencies[latencies.len() / 2])
+    }
+
+    pub fn is_failing(&self) -> bool {
+        self.recent().count() >= MIN_SAMPLES_FOR_HEALTH
+            && self
+                .success_rate()
+                .is_some_and(|rate| rate < UNHEALTHY_SUCCESS_RATE)
+    }
+}
+
+impl Attempt {
+    fn is_recent(&self) -> bool {
+        self.at.elapsed() < STATS_MAX_AGE
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StatsRegistry {
+    stats: DashMap<Model, ModelStats>,
+}
+
+impl StatsRegistry {
+    pub fn record(&self, model: &Model, success: bool, latency: Duration) {
+        self.stats
+            .entry(model.clone())
+            .or_default()
+            .record(success, latency);
+    }
+
+    pub fn record_validation_failure(&self, model: &Model, latency: Duration) {
+        self.stats
+            .entry(model.clone())
+            .or_default()
+            .record_validation_failure(latency);
+    }
+
+    pub fn validation_failures(&self, model: &Model) -> u64 {
+        self.stats.get(model).map_or(0, |s| s.validation_failures())
+    }
+
+    pub fn p50_latency(&self, model: &Model) -> Option<Duration> {
+        self.stats.get(model).and_then(|s| s.p50_latency())
+    }
+
+    pub fn success_rate(&self, model: &Model) -> Option<f64> {
+        self.stats.get(model).and_then(|s| s.success_rate())
+    }
+
+    pub fn is_failing(&self, model: &Model) -> bool {
+        self.stats.get(model).is_some_and(|s| s.is_failing())
+    }
+}
+
+#[test]
+fn model_stats_test() {
+    let mut stats = ModelStats::default();
+    assert_eq!(stats.success_rate(), None);
+    assert!(!stats.is_failing());
+
+    for ms in [300, 100, 200] {
+        stats.record(true, Duration::from_millis(ms));
+    }
+    assert_eq!(stats.p50_latency(), Some(Duration::from_millis(200)));
+
+    for _ in 0..4 {
+        stats.record(false, Duration::from_secs(30));
+    }
+    assert!(stats.is_failing());
+    assert_eq!(stats.p50_latency(), Some(Duration::from_millis(200)));
+
+    stats.record_validation_failure(Duration::from_millis(100));
+    assert_eq!(stats.validation_failures(), 1);
+    assert_eq!(stats.success_rate(), Some(3.0 / 8.0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn model_stats_age_test() {
+    let mut stats = ModelStats::default();
+    for _ in 0..5 {
+        stats.record(false, Duration::from_secs(30));
+    }
+    assert!(stats.is_failing());
+
+    tokio::time::advance(STATS_MAX_AGE).await;
+    assert!(!stats.is_failing());
+    assert_eq!(stats.success_rate(), None);
+
+    stats.record(true, Duration::from_millis(100));
+    assert_eq!(stats.success_rate(), Some(1.0));
+    assert_eq!(stats.attempts.len(), 1);
+}
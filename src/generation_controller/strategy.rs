@@ -0,0 +1,180 @@
+use crate::generation_controller::stats::StatsRegistry;
+use crate::generation_controller::{ModelPool, PooledModel};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStrategy {
+    #[default]
+    WeightedRandom,
+    RoundRobin,
+    /// Models are tried in the order they are listed in the config.
+    Priority,
+    /// Models with the lowest recent median latency go first, untried models after all others.
+    FastestP50,
+}
+
+impl SelectionStrategy {
+    /// Returns the order in which models should be tried, failing models go after healthy ones
+    /// and last resort models after all others.
+    pub fn order<'a>(
+        &self,
+        models: &'a ModelPool,
+        stats: &StatsRegistry,
+        turn: usize,
+    ) -> Vec<&'a PooledModel> {
+        let mut ordered: Vec<&PooledModel> = match self {
+            SelectionStrategy::WeightedRandom => weighted_order(models),
+            SelectionStrategy::RoundRobin => {
+                let mut ordered: Vec<&PooledModel> = models.iter().collect();
+                if !ordered.is_empty() {
+                    let len = ordered.len();
+                    ordered.rotate_left(turn % len);
+                }
+                ordered
+            }
+            SelectionStrategy::Priority => models.iter().collect(),
+            SelectionStrategy::FastestP50 => {
+                let mut ordered: Vec<&PooledModel> = models.iter().collect();
+                ordered.sort_by_key(|m| {
+                    let p50 = stats.p50_latency(&m.rephraser.get_model_name());
+                    (p50.is_none(), p50)
+                });
+                ordered
+            }
+        };
+
+        // Stable sort keeps the strategy order inside healthy and failing groups
+        ordered.sort_by_key(|m| {
+            (
+                m.last_resort,
+                stats.is_failing(&m.rephraser.get_model_name()),
+            )
+        });
+        ordered
+    }
+}
+
+/// Random order where models with bigger weight tend to come first
+/// (weighted sampling without replacement, Efraimidis–Spirakis).
+fn weighted_order(models: &ModelPool) -> Vec<&PooledModel> {
+    let mut keyed: Vec<(f64, &PooledModel)> = models
+        .iter()
+        .map(|m| (rand::random::<f64>().powf(1.0 / m.weight.max(1) as f64), m))
+        .collect();
+
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, m)| m).collect()
+}
+
+#[cfg(test)]
+fn named_pool(names: &[&'static str]) -> ModelPool {
+    use crate::common::Model;
+    use crate::generation_controller::MockContentRephraser;
+    use std::sync::Arc;
+
+    names
+        .iter()
+        .map(|name| {
+            let mut mock = MockContentRephraser::new();
+            mock.expect_get_model_name().return_const(Model::new(*name));
+            PooledModel::new(Arc::new(mock), 1)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn names(ordered: Vec<&PooledModel>) -> Vec<String> {
+    ordered
+        .into_iter()
+        .map(|m| m.rephraser.get_model_name().to_string())
+        .collect()
+}
+
+#[test]
+fn selection_strategy_priority_and_round_robin_test() {
+    let pool = named_pool(&["a", "b", "c"]);
+    let stats = StatsRegistry::default();
+
+    assert_eq!(
+        names(SelectionStrategy::Priority.order(&pool, &stats, 7)),
+        ["a", "b", "c"]
+    );
+    assert_eq!(
+        names(SelectionStrategy::RoundRobin.order(&pool, &stats, 4)),
+        ["b", "c", "a"]
+    );
+}
+
+#[test]
+fn selection_strategy_health_test() {
+    use crate::common::Model;
+    use std::time::Duration;
+
+    let pool = named_pool(&["untried", "a", "b", "c"]);
+    let stats = StatsRegistry::default();
+
+    stats.record(&Model::new("a"), true, Duration::from_millis(900));
+    stats.record(&Model::new("b"), true, Duration::from_millis(100));
+    for _ in 0..5 {
+        stats.record(&Model::new("c"), false, Duration::from_millis(10));
+    }
+
+    assert_eq!(
+        names(SelectionStrategy::FastestP50.order(&pool, &stats, 0)),
+        ["b", "a", "untried", "c"]
+    );
+    assert_eq!(
+        names(SelectionStrategy::Priority.order(&pool, &stats, 0)),
+        ["untried", "a", "b", "c"]
+    );
+
+    for _ in 0..5 {
+        stats.record(&Model::new("a"), false, Duration::from_millis(10));
+    }
+    assert_eq!(
+        names(SelectionStrategy::Priority.order(&pool, &stats, 0)),
+        ["untried", "b", "a", "c"]
+    );
+}
+
+#[test]
+fn selection_strategy_last_resort_test() {
+    let mut pool = named_pool(&["offline", "a", "b"]);
+    pool[0] = pool[0].clone().with_last_resort();
+    let stats = StatsRegistry::default();
+
+    for strategy in [
+        SelectionStrategy::WeightedRandom,
+        SelectionStrategy::RoundRobin,
+        SelectionStrategy::Priority,
+        SelectionStrategy::FastestP50,
+    ] {
+        let ordered = names(strategy.order(&pool, &stats, 2));
+        assert_eq!(ordered.last().map(String::as_str), Some("offline"));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn selection_strategy_recovery_test() {
+    use crate::common::Model;
+    use std::time::Duration;
+
+    let pool = named_pool(&["primary", "secondary"]);
+    let stats = StatsRegistry::default();
+
+    for _ in 0..5 {
+        stats.record(&Model::new("primary"), false, Duration::from_secs(30));
+    }
+    assert_eq!(
+        names(SelectionStrategy::Priority.order(&pool, &stats, 0)),
+        ["secondary", "primary"]
+    );
+
+    // Old failures are forgotten even though the demoted model got no new attempts
+    tokio::time::advance(Duration::from_secs(10 * 60)).await;
+    assert_eq!(
+        names(SelectionStrategy::Priority.order(&pool, &stats, 0)),
+        ["primary", "secondary"]
+    );
+}
@@ -0,0 +1,158 @@
+use crate::errors::ValidationError;
+use crate::errors::ValidationError::{MissingKeyword, NumbersChanged, TooLong};
+use serde::Deserialize;
+
+const DEFAULT_MAX_LENGTH: usize = 500;
+const DEFAULT_REQUIRED_KEYWORDS: [&str; 2] = ["нефорск", "пятниц"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ValidationConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Maximum length of the generated text in characters.
+    #[serde(default = "default_max_length")]
+    pub max_length: usize,
+    /// Case insensitive word stems, so any declension of «нефорский» matches "нефорск".
+    #[serde(default = "default_required_keywords")]
+    pub required_keywords: Vec<String>,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        ValidationConfig {
+            enabled: default_enabled(),
+            max_length: DEFAULT_MAX_LENGTH,
+            required_keywords: default_required_keywords(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_max_length() -> usize {
+    DEFAULT_MAX_LENGTH
+}
+
+fn default_required_keywords() -> Vec<String> {
+    DEFAULT_REQUIRED_KEYWORDS.map(String::from).to_vec()
+}
+
+/// Checks model output against the rules from the prompt, repairing what can be repaired.
+#[derive(Debug, Clone)]
+pub struct OutputValidator {
+    config: ValidationConfig,
+}
+
+impl OutputValidator {
+    pub fn new(config: ValidationConfig) -> Self {
+        OutputValidator { config }
+    }
+
+    pub fn validate(&self, input: &str, output: &str) -> Result<String, ValidationError> {
+        if !self.config.enabled {
+            return Ok(output.to_string());
+        }
+
+        let text = repair(output);
+
+        let length = text.chars().count();
+        if length > self.config.max_length {
+            return Err(TooLong(length, self.config.max_length));
+        }
+
+        let lowercase = text.to_lowercase();
+        if let Some(keyword) = self
+            .config
+            .required_keywords
+            .iter()
+            .find(|k| !lowercase.contains(&k.to_lowercase()))
+        {
+            return Err(MissingKeyword(keyword.clone()));
+        }
+
+        if !preserves_numbers(input, &text) {
+            return Err(NumbersChanged);
+        }
+
+        Ok(text)
+    }
+
+    /// Chat replies keep only the length limit, the countdown rules don't apply to them.
+    pub fn validate_reply(&self, output: &str) -> Result<String, ValidationError> {
+        if !self.config.enabled {
+            return Ok(output.to_string());
+        }
+
+        let text = repair(output);
+
+        let length = text.chars().count();
+        if length > self.config.max_length {
+            return Err(TooLong(length, self.config.max_length));
+        }
+
+        Ok(text)
+    }
+}
+
+/// Drops markdown bold/italic asterisks and the whitespace left around the text.
+fn repair(output: &str) -> String {
+    output.replace('*', "").trim().to_string()
+}
+
+/// Every number of the input must appear in the output in the same order.
+fn preserves_numbers(input: &str, output: &str) -> bool {
+    let expected = numbers(input);
+    let mut actual = numbers(output).into_iter();
+
+    expected.iter().all(|n| actual.any(|a| a == *n))
+}
+
+fn numbers(text: &str) -> Vec<u64> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+#[test]
+fn validator_test() {
+    let validator = OutputValidator::new(ValidationConfig::default());
+    let input = "До нефорской пятницы осталось: 2 дня, 5 часов 🕷️";
+
+    assert_eq!(
+        validator.validate(
+            input,
+            "  Тик-так! До **нефорской Пятницы** 2 дня и 5 часов 😈 "
+        ),
+        Ok("Тик-так! До нефорской Пятницы 2 дня и 5 часов 😈".to_string())
+    );
+    assert_eq!(
+        validator.validate(input, "Пятница через 2 дня и 5 часов"),
+        Err(MissingKeyword("нефорск".to_string()))
+    );
+    assert_eq!(
+        validator.validate(input, "Нефорская пятница через 2 дня и 6 часов"),
+        Err(NumbersChanged)
+    );
+    assert_eq!(
+        validator.validate(input, "Нефорская пятница через 5 часов и 2 дня"),
+        Err(NumbersChanged)
+    );
+    assert_eq!(
+        validator.validate(
+            input,
+            &format!("Нефорская пятница 2 5 {}", "🔥".repeat(500))
+        ),
+        Err(TooLong(522, 500))
+    );
+
+    assert_eq!(
+        validator.validate_reply(" Привет, **котаны** 🖤 "),
+        Ok("Привет, котаны 🖤".to_string())
+    );
+    assert_eq!(
+        validator.validate_reply(&"🔥".repeat(501)),
+        Err(TooLong(501, 500))
+    );
+}
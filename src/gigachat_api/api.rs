@@ -1,43 +1,91 @@
-use crate::common::{Model, ensure_success};
+use crate::common::Model;
+use crate::config::{GigaChatProviderConfig, ProviderConfig};
 use crate::errors::ApiError;
 use crate::errors::ApiError::{
-    ApiClientBuildError, ApiStatusError, CertParseError, DecodeResponseError, NoContent,
-    RequestError,
+    ApiClientBuildError, ApiKeyNotFound, ApiStatusError, CertParseError, DecodeResponseError,
+    NoContent, RequestError,
 };
-use crate::generation_controller::ContentRephraser;
+use crate::generation_controller::{Completion, ContentRephraser, GenerationRequest, TextProgress};
 use crate::gigachat_api::dto::{
     GigaChatAuthRequest, GigaChatAuthResponse, GigaChatGenerateTextRequest,
-    GigaChatGenerateTextResponse, GigaChatMessage, GigaChatRole,
+    GigaChatGenerateTextResponse, GigaChatMessage, GigaChatRole, GigaChatStreamChunk,
 };
+use crate::http_retry::{RetryPolicy, send_with_retry};
+use crate::streaming::{SSE_DONE, read_lines, sse_data};
 use async_trait::async_trait;
 use log::debug;
 use reqwest::{Certificate, Client, Url};
-use std::fs;
 use std::time::{Duration, SystemTime};
+use std::{env, fs};
 use tokio::sync::Mutex;
 use tracing::{error, info, instrument, warn};
 use uuid::Uuid;
 
-#[allow(unused)]
+/// Tokens expiring sooner than this are refreshed before the request.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(3);
+
+#[derive(Debug)]
+struct AccessToken {
+    value: String,
+    expires_at: SystemTime,
+}
+
+impl AccessToken {
+    /// A token is reusable unless it expires soon or the API has just rejected it.
+    fn is_usable(&self, now: SystemTime, rejected: Option<&str>) -> bool {
+        !self.value.is_empty()
+            && self.expires_at > now + TOKEN_EXPIRY_MARGIN
+            && rejected != Some(self.value.as_str())
+    }
+}
+
 #[derive(Debug)]
 pub struct GigaChatApi {
     pub server: Client,
     client_id: String,
     client_secret: String,
-    access_token: Mutex<String>,
-    access_token_expire_at: Mutex<SystemTime>,
+    /// Held for the whole refresh, so concurrent requests wait for one token instead of
+    /// fetching their own.
+    access_token: Mutex<AccessToken>,
+    completions_url: Url,
+    auth_url: Url,
+    model_name: String,
+    retry_policy: RetryPolicy,
     model: Model,
 }
 
-#[allow(unused)]
 impl GigaChatApi {
-    pub fn new(client_id: String, client_secret: String) -> Result<Self, ApiError> {
-        let cert_pem = fs::read("cert.crt")?;
+    pub fn new(
+        provider: &ProviderConfig,
+        config: &GigaChatProviderConfig,
+    ) -> Result<Self, ApiError> {
+        let client_id = env::var(&config.client_id_env)
+            .map_err(|e| ApiKeyNotFound(config.client_id_env.clone(), e))?;
+        let client_secret = env::var(&config.client_secret_env)
+            .map_err(|e| ApiKeyNotFound(config.client_secret_env.clone(), e))?;
+
+        Self::with_credentials(provider, config, client_id, client_secret)
+    }
+
+    pub fn with_credentials(
+        provider: &ProviderConfig,
+        config: &GigaChatProviderConfig,
+        client_id: String,
+        client_secret: String,
+    ) -> Result<Self, ApiError> {
+        let completions_url = Url::parse(&format!(
+            "{}/chat/completions",
+            config.base_url.trim_end_matches('/')
+        ))?;
+        let auth_url = Url::parse(&config.auth_url)?;
+
+        let cert_pem = fs::read(&config.cert_path)?;
 
         let cert = Certificate::from_pem(&cert_pem).map_err(CertParseError)?;
 
         let custom_client = Client::builder()
             .add_root_certificate(cert)
+            .timeout(Duration::from_secs(provider.timeout_secs))
             .build()
             .map_err(ApiClientBuildError)?;
 
@@ -45,25 +93,42 @@ impl GigaChatApi {
             server: custom_client,
             client_id,
             client_secret,
-            access_token: Mutex::new(String::new()),
-            access_token_expire_at: Mutex::new(SystemTime::UNIX_EPOCH),
-            model: Model::Gigachat,
+            access_token: Mutex::new(AccessToken {
+                value: String::new(),
+                expires_at: SystemTime::UNIX_EPOCH,
+            }),
+            completions_url,
+            auth_url,
+            model_name: config.model.clone(),
+            retry_policy: RetryPolicy::from(provider),
+            model: Model::new(provider.name.as_str()),
         })
     }
 
+    /// Returns a valid token, refreshing it at most once for all concurrent callers.
+    /// `rejected` is the token the API answered 401 to, it is replaced unless
+    /// another request already did that.
+    async fn auth_token(&self, rejected: Option<&str>) -> Result<String, ApiError> {
+        let mut token = self.access_token.lock().await;
+
+        if !token.is_usable(SystemTime::now(), rejected) {
+            *token = self.refresh_auth_token().await?;
+        }
+
+        Ok(token.value.clone())
+    }
+
     #[instrument(skip(self), err)]
-    async fn refresh_auth_token(&self) -> Result<(), ApiError> {
+    async fn refresh_auth_token(&self) -> Result<AccessToken, ApiError> {
         info!("Starting to refresh token");
 
-        let auth_refresh_url = Url::parse("https://ngw.devices.sberbank.ru:9443/api/v2/oauth")?;
-
         let raw_req = GigaChatAuthRequest {
             scope: "GIGACHAT_API_PERS".to_string(),
         };
 
         let response = self
             .server
-            .post(auth_refresh_url)
+            .post(self.auth_url.clone())
             .basic_auth(&self.client_id, Some(&self.client_secret))
             .header("RqUID", Uuid::new_v4().to_string())
             .form(&raw_req)
@@ -76,7 +141,7 @@ impl GigaChatApi {
             let body = response.text().await.unwrap_or_default();
             error!(%status, %body, "Failed to refresh token");
             return Err(ApiStatusError {
-                model: Model::Gigachat,
+                model: self.model.clone(),
                 status,
                 body,
             });
@@ -86,102 +151,339 @@ impl GigaChatApi {
 
         let auth_response: GigaChatAuthResponse = serde_json::from_str(&resp_text)?;
 
-        let mut access_token_guard = self.access_token.lock().await;
-        let mut access_token_expire_at_guard = self.access_token_expire_at.lock().await;
+        info!("Successfully refreshed token");
+        Ok(AccessToken {
+            value: auth_response.access_token,
+            expires_at: auth_response.expires_at,
+        })
+    }
+}
 
-        *access_token_guard = auth_response.access_token;
-        *access_token_expire_at_guard = auth_response.expires_at;
+impl GigaChatApi {
+    /// Sends the completion request, refreshing the token once if GigaChat rejects it.
+    async fn send(
+        &self,
+        request: &GenerationRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response, ApiError> {
+        let mut access_token = self.auth_token(None).await?;
 
-        info!("Successfully refreshed token");
-        Ok(())
+        let system_message =
+            GigaChatMessage::new(GigaChatRole::System, request.system_prompt.clone());
+        let message_to_rephrase = GigaChatMessage::new(GigaChatRole::User, request.text.clone());
+        let body = GigaChatGenerateTextRequest {
+            model: self.model_name.clone(),
+            messages: vec![system_message, message_to_rephrase],
+            stream,
+        };
+
+        let mut refreshed = false;
+        loop {
+            let auth_header = format!("Bearer {}", access_token);
+
+            debug!("Sending generation request to GigaChat...");
+            let result = send_with_retry(&self.model, &self.retry_policy, || {
+                self.server
+                    .post(self.completions_url.clone())
+                    .header(reqwest::header::AUTHORIZATION, &auth_header)
+                    .json(&body)
+            })
+            .await;
+
+            match result {
+                Err(ApiStatusError { status, .. })
+                    if status == reqwest::StatusCode::UNAUTHORIZED && !refreshed =>
+                {
+                    info!("Refreshing token and retrying...");
+                    access_token = self.auth_token(Some(&access_token)).await?;
+                    refreshed = true;
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to generate content through GigaChat");
+                    return Err(e);
+                }
+                Ok(response) => return Ok(response),
+            }
+        }
     }
 }
 
 #[async_trait]
 impl ContentRephraser for GigaChatApi {
-    #[instrument(skip(self, current_text), err)]
-    async fn rephrase_text(&self, current_text: &str) -> Result<String, ApiError> {
+    #[instrument(skip(self, request), err)]
+    async fn rephrase_text(&self, request: &GenerationRequest) -> Result<Completion, ApiError> {
         info!("Starting to rephrase text");
 
-        let is_expired = {
-            let expire_at = self.access_token_expire_at.lock().await;
-            *expire_at <= (SystemTime::now() + Duration::from_secs(3))
-        };
+        let response = self.send(request, false).await?;
+
+        let response: GigaChatGenerateTextResponse =
+            response.json().await.map_err(DecodeResponseError)?;
 
-        if is_expired {
-            self.refresh_auth_token().await?
+        if let Some(new_text) = response.choices.into_iter().next() {
+            info!(usage = ?response.usage, "Text rephrased successfully");
+            return Ok(Completion::new(new_text.message.content, response.usage));
         }
 
-        let system_message = GigaChatMessage::new_system_message();
-        let message_to_rephrase =
-            GigaChatMessage::new(GigaChatRole::User, current_text.to_string());
-        let request = GigaChatGenerateTextRequest {
-            model: "GigaChat-2".to_string(),
-            messages: vec![system_message, message_to_rephrase],
-        };
+        warn!("GigaChat returned 200 OK but empty choices");
+        Err(NoContent)
+    }
+
+    /// GigaChat streams chunks as server-sent events, the same way as OpenAI.
+    #[instrument(skip(self, request, progress), err)]
+    async fn rephrase_text_streaming(
+        &self,
+        request: &GenerationRequest,
+        progress: &TextProgress,
+    ) -> Result<Completion, ApiError> {
+        info!("Starting to stream text");
 
-        for attempt in 1..=2 {
-            debug!("{} {}", attempt, "Sending generation request...");
+        let response = self.send(request, true).await?;
 
-            let auth_header = {
-                let current_access_token = self.access_token.lock().await;
-                format!("Bearer {}", current_access_token)
+        let mut text = String::new();
+        let mut usage = None;
+
+        let finished = read_lines(response, |line| {
+            let Some(data) = sse_data(line) else {
+                return Ok(false);
             };
+            if data == SSE_DONE {
+                return Ok(true);
+            }
 
-            let generate_content_url =
-                Url::parse("https://gigachat.devices.sberbank.ru/api/v1/chat/completions")?;
+            let chunk: GigaChatStreamChunk = serde_json::from_str(data)?;
+            usage = chunk.usage.or(usage);
 
-            debug!("Sending generation request to GigaChat...");
-            let response = self
-                .server
-                .post(generate_content_url)
-                .header(reqwest::header::AUTHORIZATION, auth_header)
-                .json(&request)
-                .send()
-                .await
-                .map_err(RequestError)?;
-
-            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
-                if attempt == 1 {
-                    info!("Refreshing token and retrying...");
-                    self.refresh_auth_token().await?;
-                    continue;
-                } else {
-                    let body = response.text().await.unwrap_or_default();
-                    error!(%body, "Failed to authenticate even after refresh");
-                    return Err(ApiStatusError {
-                        model: Model::Gigachat,
-                        status: reqwest::StatusCode::UNAUTHORIZED,
-                        body,
-                    });
-                }
+            if let Some(delta) = chunk.choices.into_iter().find_map(|c| c.delta.content) {
+                text.push_str(&delta);
+                progress.send_modify(|shown| shown.push_str(&delta));
             }
 
-            let response = match ensure_success(self.model, response).await {
-                Ok(resp) => resp,
-                Err(e) => {
-                    if attempt == 1 {
-                        continue;
-                    }
-                    error!(error = %e, "Failed to generate content through GigaChat");
-                    return Err(e);
-                }
-            };
+            Ok(false)
+        })
+        .await?;
 
-            let response: GigaChatGenerateTextResponse =
-                response.json().await.map_err(DecodeResponseError)?;
+        if !finished {
+            warn!("GigaChat stream ended without done marker");
+        }
 
-            if let Some(new_text) = response.choices.into_iter().next() {
-                info!("Text rephrased successfully");
-                return Ok(new_text.message.content);
-            }
+        if text.trim().is_empty() {
+            warn!("GigaChat streamed no content");
+            return Err(NoContent);
         }
 
-        warn!("GigaChat returned 200 OK but empty choices");
-        Err(NoContent)
+        info!(?usage, "Text streamed successfully");
+        Ok(Completion::new(text, usage))
     }
 
     fn get_model_name(&self) -> Model {
-        self.model
+        self.model.clone()
     }
 }
+
+#[test]
+fn access_token_is_usable_test() {
+    let now = SystemTime::now();
+    let token = AccessToken {
+        value: String::from("token"),
+        expires_at: now + Duration::from_secs(60),
+    };
+
+    assert!(token.is_usable(now, None));
+    assert!(token.is_usable(now, Some("older token")));
+    assert!(!token.is_usable(now, Some("token")));
+    assert!(!token.is_usable(now + Duration::from_secs(58), None));
+
+    let empty = AccessToken {
+        value: String::new(),
+        expires_at: now + Duration::from_secs(60),
+    };
+    assert!(!empty.is_usable(now, None));
+}
+
+#[cfg(test)]
+fn fake_api(server: &crate::test_server::FakeServer) -> GigaChatApi {
+    use crate::config::ProviderKind;
+
+    let raw = format!(
+        r#"
+        name = "Fake"
+        kind = "gigachat"
+        model = "GigaChat-2"
+        client_id_env = "FAKE_CLIENT_ID"
+        client_secret_env = "FAKE_CLIENT_SECRET"
+        base_url = "{url}/api/v1"
+        auth_url = "{url}/api/v2/oauth"
+        retries = 1
+        backoff_base_ms = 1
+        backoff_max_ms = 5
+        "#,
+        url = server.url()
+    );
+
+    let provider: ProviderConfig = toml::from_str(&raw).unwrap();
+    let ProviderKind::GigaChat(config) = &provider.kind else {
+        unreachable!("provider kind is gigachat");
+    };
+
+    GigaChatApi::with_credentials(&provider, config, "id".to_string(), "secret".to_string())
+        .unwrap()
+}
+
+#[cfg(test)]
+const COMPLETIONS_PATH: &str = "/api/v1/chat/completions";
+#[cfg(test)]
+const AUTH_PATH: &str = "/api/v2/oauth";
+
+#[tokio::test]
+async fn gigachat_api_token_refresh_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(
+        AUTH_PATH,
+        [
+            FakeResponse::access_token("first"),
+            FakeResponse::access_token("second"),
+        ],
+    );
+    server.respond(
+        COMPLETIONS_PATH,
+        [
+            FakeResponse::completion("Нефорская пятница"),
+            FakeResponse::json(401, r#"{"message": "token expired"}"#),
+            FakeResponse::completion("Снова нефорская пятница"),
+        ],
+    );
+
+    let api = fake_api(&server);
+
+    let completion = api
+        .rephrase_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert!(completion.usage.is_some());
+
+    let completion = api
+        .rephrase_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(completion.text, "Снова нефорская пятница");
+
+    let auth = server.requests(AUTH_PATH);
+    assert_eq!(auth.len(), 2);
+    assert!(auth[0].headers["authorization"].starts_with("Basic "));
+    assert!(auth[0].headers.contains_key("rquid"));
+
+    let authorizations: Vec<String> = server
+        .requests(COMPLETIONS_PATH)
+        .iter()
+        .map(|r| r.headers["authorization"].clone())
+        .collect();
+    assert_eq!(
+        authorizations,
+        ["Bearer first", "Bearer first", "Bearer second"]
+    );
+}
+
+#[tokio::test]
+async fn gigachat_api_single_flight_refresh_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(AUTH_PATH, [FakeResponse::access_token("token")]);
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::completion("Нефорская пятница")],
+    );
+
+    let api = fake_api(&server);
+    let request = test_request("some test text");
+
+    let results = tokio::join!(
+        api.rephrase_text(&request),
+        api.rephrase_text(&request),
+        api.rephrase_text(&request),
+        api.rephrase_text(&request),
+    );
+    assert!(results.0.is_ok() && results.1.is_ok() && results.2.is_ok() && results.3.is_ok());
+    assert_eq!(server.requests(AUTH_PATH).len(), 1);
+}
+
+#[tokio::test]
+async fn gigachat_api_bad_response_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(AUTH_PATH, [FakeResponse::access_token("token")]);
+    let api = fake_api(&server);
+
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::json(200, r#"{"choices": []}"#)],
+    );
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(NoContent)));
+
+    server.respond(COMPLETIONS_PATH, [FakeResponse::json(200, "not json")]);
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(DecodeResponseError(_))));
+
+    server.respond(COMPLETIONS_PATH, [FakeResponse::json(429, "{}")]);
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(
+        result,
+        Err(ApiStatusError { status, .. }) if status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    ));
+
+    let server = FakeServer::start().await;
+    server.respond(
+        AUTH_PATH,
+        [FakeResponse::json(401, r#"{"message": "bad credentials"}"#)],
+    );
+    let result = fake_api(&server)
+        .rephrase_text(&test_request("some test text"))
+        .await;
+    assert!(matches!(result, Err(ApiStatusError { .. })));
+    assert_eq!(server.requests(COMPLETIONS_PATH).len(), 0);
+}
+
+#[tokio::test]
+async fn gigachat_api_streaming_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(
+        AUTH_PATH,
+        [
+            FakeResponse::access_token("first"),
+            FakeResponse::access_token("second"),
+        ],
+    );
+    server.respond(
+        COMPLETIONS_PATH,
+        [
+            FakeResponse::json(401, r#"{"message": "token expired"}"#),
+            FakeResponse::stream(&["Нефорская", " пятница"]),
+        ],
+    );
+
+    let (progress, shown) = tokio::sync::watch::channel(String::new());
+    let completion = fake_api(&server)
+        .rephrase_text_streaming(&test_request("some test text"), &progress)
+        .await
+        .unwrap();
+
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert!(completion.usage.is_some());
+    assert_eq!(*shown.borrow(), "Нефорская пятница");
+
+    let requests = server.requests(COMPLETIONS_PATH);
+    assert_eq!(requests.len(), 2);
+    let body: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+    assert_eq!(body["stream"], true);
+}
@@ -1,4 +1,4 @@
-use crate::constants::TEXT_MODIFY_PROMPT;
+use crate::generation_controller::TokenUsage;
 use serde::{Deserialize, Serialize};
 use serde_with::{TimestampMilliSeconds, serde_as};
 use std::time;
@@ -20,6 +20,8 @@ pub struct GigaChatAuthResponse {
 pub struct GigaChatGenerateTextRequest {
     pub model: String,
     pub messages: Vec<GigaChatMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -51,19 +53,13 @@ impl GigaChatMessage {
     pub fn new(role: GigaChatRole, content: String) -> Self {
         GigaChatMessage { role, content }
     }
-
-    // TODO: Мб лучше создавать не тут
-    pub fn new_system_message() -> Self {
-        GigaChatMessage {
-            role: GigaChatRole::System,
-            content: TEXT_MODIFY_PROMPT.to_string(),
-        }
-    }
 }
 
 #[derive(Deserialize)]
 pub struct GigaChatGenerateTextResponse {
     pub choices: Vec<GigaChatChoice>,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
 }
 
 #[derive(Deserialize)]
@@ -75,3 +71,24 @@ pub struct GigaChatChoice {
 pub struct GigaChatChoiceMessage {
     pub content: String,
 }
+
+/// One server-sent event of a streamed answer.
+#[derive(Deserialize)]
+pub struct GigaChatStreamChunk {
+    #[serde(default)]
+    pub choices: Vec<GigaChatStreamChoice>,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+#[derive(Deserialize)]
+pub struct GigaChatStreamChoice {
+    #[serde(default)]
+    pub delta: GigaChatDelta,
+}
+
+#[derive(Deserialize, Default)]
+pub struct GigaChatDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+}
@@ -0,0 +1,123 @@
+use crate::commands::AdminCommand;
+use crate::config::AdminIds;
+use crate::errors::ApiError;
+use crate::generation_controller::ModelHealth;
+use crate::generation_controller::budget::BudgetConfig;
+use crate::handlers::prompt_templates::{
+    activate_template, create_template, edit_template, list_templates, preview_template,
+};
+use crate::handlers::root_handler::{ChatSettingsStore, ContentGenerator, PromptTemplateStore};
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use tracing::{info, instrument, warn};
+
+#[instrument(skip(bot, msg, admins, generator, prompts, settings_store))]
+pub async fn handle_admin_command(
+    bot: Bot,
+    msg: Message,
+    cmd: AdminCommand,
+    admins: Arc<AdminIds>,
+    generator: Arc<dyn ContentGenerator>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+) -> Result<(), ApiError> {
+    let Some(user) = msg.from.as_ref().filter(|u| admins.contains(u.id)) else {
+        warn!(user = ?msg.from.as_ref().map(|u| u.id), "Admin command from non admin user");
+        bot.send_message(msg.chat.id, "Команда доступна только администраторам бота")
+            .await?;
+        return Ok(());
+    };
+
+    match cmd {
+        AdminCommand::Breakers => breakers(bot, msg.chat.id, generator).await?,
+
+        AdminCommand::Prompts => list_templates(bot, msg.chat.id, prompts, settings_store).await?,
+
+        AdminCommand::PromptCreate(args) => create_template(bot, &msg, args, prompts).await?,
+
+        AdminCommand::PromptEdit(args) => edit_template(bot, msg.chat.id, args, prompts).await?,
+
+        AdminCommand::PromptPreview(name) => {
+            preview_template(bot, msg.chat.id, name, generator, prompts, settings_store).await?
+        }
+
+        AdminCommand::PromptActivate(name) => {
+            activate_template(bot, msg.chat.id, name, prompts, settings_store).await?
+        }
+    }
+
+    info!(admin = %user.id, "Admin command handled");
+    Ok(())
+}
+
+async fn breakers(
+    bot: Bot,
+    chat_id: ChatId,
+    generator: Arc<dyn ContentGenerator>,
+) -> Result<(), ApiError> {
+    let health = generator.model_health();
+
+    if health.is_empty() {
+        bot.send_message(chat_id, "Модели не настроены").await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = health.iter().map(describe_health).collect();
+
+    bot.send_message(
+        chat_id,
+        format!("Состояние моделей:\n\n{}", lines.join("\n")),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn describe_health(health: &ModelHealth) -> String {
+    let mut line = format!(
+        "{} — {}, ошибок подряд: {}",
+        health.model, health.breaker.state, health.breaker.consecutive_failures
+    );
+
+    if let Some(retry_in) = health.breaker.retry_in {
+        line.push_str(&format!(", проба через {} с", retry_in.as_secs()));
+    }
+
+    if let Some(rate) = health.success_rate {
+        line.push_str(&format!(", успешность: {:.0}%", rate * 100.0));
+    }
+
+    if let Some(p50) = health.p50_latency {
+        line.push_str(&format!(", p50: {:.1} с", p50.as_secs_f64()));
+    }
+
+    if health.validation_failures > 0 {
+        line.push_str(&format!(
+            ", невалидных ответов: {}",
+            health.validation_failures
+        ));
+    }
+
+    let budget = &health.budget;
+    if budget.spent_this_month > 0.0 || budget.limits != BudgetConfig::default() {
+        line.push_str(&format!(
+            ", расходы: {} за день, {} за месяц",
+            describe_spending(budget.spent_today, budget.limits.daily),
+            describe_spending(budget.spent_this_month, budget.limits.monthly)
+        ));
+    }
+
+    if budget.exhausted {
+        line.push_str(", бюджет исчерпан");
+    }
+
+    line
+}
+
+fn describe_spending(spent: f64, limit: Option<f64>) -> String {
+    match limit {
+        Some(limit) => format!("{:.4} из {:.2}", spent, limit),
+        None => format!("{:.4}", spent),
+    }
+}
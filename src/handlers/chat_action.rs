@@ -0,0 +1,74 @@
+use std::time::Duration;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use teloxide::types::ChatAction;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Telegram shows an action for about 5 seconds, so it is repeated a bit earlier.
+const CHAT_ACTION_INTERVAL: Duration = Duration::from_secs(4);
+
+/// Keeps showing the chat action until dropped, so it also stops on early returns and errors.
+pub struct ChatActionGuard {
+    task: JoinHandle<()>,
+}
+
+impl ChatActionGuard {
+    pub fn start(bot: &Bot, chat_id: ChatId, action: ChatAction) -> Self {
+        let bot = bot.clone();
+
+        let task = tokio::spawn(async move {
+            loop {
+                if let Err(e) = bot.send_chat_action(chat_id, action).await {
+                    warn!(error = %e, ?action, "Failed to send chat action");
+                }
+
+                tokio::time::sleep(CHAT_ACTION_INTERVAL).await;
+            }
+        });
+
+        ChatActionGuard { task }
+    }
+}
+
+impl Drop for ChatActionGuard {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Awaits the future while the chat shows the action, e.g. "typing…".
+pub async fn with_chat_action<F: Future>(
+    bot: &Bot,
+    chat_id: ChatId,
+    action: ChatAction,
+    future: F,
+) -> F::Output {
+    let _guard = ChatActionGuard::start(bot, chat_id, action);
+    future.await
+}
+
+#[tokio::test]
+async fn chat_action_guard_test() {
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    const ACTION_PATH: &str = "/botTOKEN/SendChatAction";
+
+    let server = FakeServer::start().await;
+    server.respond(
+        ACTION_PATH,
+        [FakeResponse::json(200, r#"{"ok": true, "result": true}"#)],
+    );
+    let bot = Bot::new("TOKEN").set_api_url(server.url().parse().unwrap());
+
+    let answer = with_chat_action(&bot, ChatId(42), ChatAction::Typing, async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        "Нефорская пятница"
+    })
+    .await;
+    assert_eq!(answer, "Нефорская пятница");
+
+    let requests = server.requests(ACTION_PATH);
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].body.contains("typing"));
+}
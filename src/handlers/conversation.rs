@@ -0,0 +1,241 @@
+use crate::config::ConversationConfig;
+use crate::constants::CONVERSATION_PROMPT;
+use crate::errors::ApiError;
+use crate::generation_controller::GenerationRequest;
+use crate::handlers::chat_action::with_chat_action;
+use crate::handlers::friday::generation_request;
+use crate::handlers::root_handler::{
+    ChatHistoryStore, ChatSettingsStore, ContentGenerator, DialogueStore, MessageStore,
+    PromptTemplateStore,
+};
+use crate::handlers::utils::get_current_state;
+use crate::repo::chat_history_storage::ChatLine;
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
+use crate::utils::get_friday_status;
+use chrono::Utc;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use teloxide::types::{ChatAction, Me, ReplyParameters};
+use tokio::time::Instant;
+use tracing::{error, info, instrument, warn};
+
+/// How the bot's own messages are signed in the transcript, the prompt refers to it.
+const BOT_AUTHOR: &str = "Ты";
+const UNKNOWN_AUTHOR: &str = "Аноним";
+
+/// Limits how often the bot answers in a chat, so a thread of mentions can't drain the budgets.
+pub struct ReplyLimiter {
+    max_replies: usize,
+    window: Duration,
+    replies: DashMap<ChatId, VecDeque<Instant>>,
+}
+
+impl ReplyLimiter {
+    pub fn new(config: &ConversationConfig) -> Self {
+        ReplyLimiter {
+            max_replies: config.max_replies,
+            window: Duration::from_secs(config.window_secs),
+            replies: DashMap::new(),
+        }
+    }
+
+    /// Takes a slot for an answer, `false` when the chat has used up the window.
+    pub fn try_acquire(&self, chat_id: ChatId, now: Instant) -> bool {
+        let mut replies = self.replies.entry(chat_id).or_default();
+        while replies
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= self.window)
+        {
+            replies.pop_front();
+        }
+
+        if replies.len() >= self.max_replies {
+            return false;
+        }
+
+        replies.push_back(now);
+        true
+    }
+}
+
+/// Messages mentioning the bot or replying to it, unless the author is in a media dialogue.
+pub fn is_conversation(msg: Message, me: Me, dialogue: Arc<dyn DialogueStore>) -> bool {
+    let replied_to_bot = msg
+        .reply_to_message()
+        .and_then(|m| m.from.as_ref())
+        .is_some_and(|u| u.id == me.id);
+    let mentioned = msg.text().is_some_and(|t| mentions(t, me.username()));
+
+    (replied_to_bot || mentioned) && get_current_state(&msg, dialogue).is_none()
+}
+
+/// Keeps every text message the bot sees as context for later answers.
+pub fn remember_message(msg: Message, me: Me, history: Arc<dyn ChatHistoryStore>) {
+    if msg.text().is_some_and(|t| t.starts_with('/')) {
+        return;
+    }
+
+    if let Some(line) = chat_line(&msg, &me) {
+        history.remember(msg.chat.id, line);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(
+    bot,
+    msg,
+    me,
+    generator,
+    message_store,
+    settings_store,
+    prompts,
+    history,
+    limiter
+))]
+pub async fn conversation_reply(
+    bot: Bot,
+    msg: Message,
+    me: Me,
+    generator: Arc<dyn ContentGenerator>,
+    message_store: Arc<dyn MessageStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    history: Arc<dyn ChatHistoryStore>,
+    limiter: Arc<ReplyLimiter>,
+) -> Result<(), ApiError> {
+    let chat_id = msg.chat.id;
+    let settings = settings_store.get_settings(chat_id).await?;
+    if !settings.replies_enabled {
+        return Ok(());
+    }
+
+    if !limiter.try_acquire(chat_id, Instant::now()) {
+        warn!("Reply rate limit reached, ignoring message");
+        return Ok(());
+    }
+
+    let mut lines = history.recent(chat_id);
+    // The replied message may be older than everything remembered, or sent before a restart
+    if let Some(line) = msg.reply_to_message().and_then(|m| chat_line(m, &me))
+        && !lines.iter().any(|l| l.message_id == line.message_id)
+    {
+        lines.insert(0, line);
+    }
+
+    // The persona is the same the chat gets its countdowns from
+    let status = get_friday_status(&settings, Utc::now());
+    let persona = generation_request(&bot, &settings, prompts.as_ref(), status).await?;
+    let request = GenerationRequest::reply(
+        persona.template,
+        format!("{}\n\n{}", persona.system_prompt, CONVERSATION_PROMPT),
+        transcript(&lines),
+    );
+
+    let result = with_chat_action(
+        &bot,
+        chat_id,
+        ChatAction::Typing,
+        generator.generate_text(&request),
+    )
+    .await;
+
+    let generation = match result {
+        Ok(generation) => generation,
+        Err(e) => {
+            error!(error = %e, "Failed to generate reply");
+            return Ok(());
+        }
+    };
+
+    let sent = bot
+        .send_message(chat_id, generation.text.clone())
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+    info!(model = %generation.model, "Replied in conversation");
+
+    history.remember(
+        chat_id,
+        ChatLine::new(sent.id, BOT_AUTHOR, generation.text.clone()),
+    );
+
+    let entry = HistoryEntry::new(chat_id, sent.id, &request, &generation);
+    if let Err(e) = message_store.add_message(&entry).await {
+        error!(error = %e, "Failed to save generation history");
+    }
+
+    Ok(())
+}
+
+fn chat_line(msg: &Message, me: &Me) -> Option<ChatLine> {
+    let text = msg.text().or(msg.caption())?;
+
+    let author = match &msg.from {
+        Some(user) if user.id == me.id => BOT_AUTHOR.to_string(),
+        Some(user) => user.full_name(),
+        None => UNKNOWN_AUTHOR.to_string(),
+    };
+
+    Some(ChatLine::new(msg.id, author, text))
+}
+
+fn mentions(text: &str, username: &str) -> bool {
+    let mention = format!("@{}", username.to_lowercase());
+
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '@'))
+        .any(|word| word == mention)
+}
+
+fn transcript(lines: &[ChatLine]) -> String {
+    lines
+        .iter()
+        .map(|l| format!("{}: {}", l.author, l.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn mentions_test() {
+    assert!(mentions(
+        "@Slay_Friday_Bot когда пятница?",
+        "slay_friday_bot"
+    ));
+    assert!(mentions("эй, @slay_friday_bot!", "slay_friday_bot"));
+    assert!(!mentions("@slay_friday_bot2 привет", "slay_friday_bot"));
+    assert!(!mentions("slay_friday_bot привет", "slay_friday_bot"));
+}
+
+#[test]
+fn transcript_test() {
+    use teloxide::types::MessageId;
+
+    let lines = [
+        ChatLine::new(MessageId(1), "Ира", "Когда пятница?"),
+        ChatLine::new(MessageId(2), BOT_AUTHOR, "Скоро, котаны 🖤"),
+    ];
+
+    assert_eq!(
+        transcript(&lines),
+        "Ира: Когда пятница?\nТы: Скоро, котаны 🖤"
+    );
+}
+
+#[test]
+fn reply_limiter_test() {
+    let limiter = ReplyLimiter::new(&ConversationConfig {
+        max_replies: 2,
+        window_secs: 60,
+        ..ConversationConfig::default()
+    });
+    let now = Instant::now();
+
+    assert!(limiter.try_acquire(ChatId(1), now));
+    assert!(limiter.try_acquire(ChatId(1), now + Duration::from_secs(10)));
+    assert!(!limiter.try_acquire(ChatId(1), now + Duration::from_secs(20)));
+    assert!(limiter.try_acquire(ChatId(2), now + Duration::from_secs(20)));
+    assert!(limiter.try_acquire(ChatId(1), now + Duration::from_secs(60)));
+}
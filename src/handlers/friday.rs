@@ -1,41 +1,212 @@
 use crate::errors::ApiError;
-use crate::handlers::root_handler::{ContentGenerator, MessageStore};
-use crate::repo::message_history_storage::HistoryEntry;
-use crate::utils::{format_time_delta, get_time_until_friday};
+use crate::formatting::format_time_delta;
+use crate::generation_controller::GenerationRequest;
+use crate::handlers::chat_action::with_chat_action;
+use crate::handlers::generation_buttons::show_generation;
+use crate::handlers::root_handler::{
+    ChatSettingsStore, ContentGenerator, MessageStore, PromptTemplateStore,
+};
+use crate::handlers::streaming::{STREAM_PLACEHOLDER, generate_into_message};
+use crate::repo::chat_settings_storage_postgres::dto::ChatSettings;
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
+use crate::repo::prompt_template_storage_postgres::dto::{
+    CHAT_TITLE_VARIABLE, DEFAULT_PROMPT_TEMPLATE, PromptTemplate, PromptVariables,
+};
+use crate::utils::{FridayStatus, get_friday_status};
+use chrono::Utc;
 use std::sync::Arc;
 use teloxide::Bot;
 use teloxide::prelude::*;
-use tracing::{error, instrument};
+use teloxide::types::ChatAction;
+use tracing::{error, instrument, warn};
 
-#[instrument(skip(bot, chat_id, generator, store))]
+#[instrument(skip(bot, chat_id, generator, store, settings_store, prompts))]
 pub async fn friday(
     bot: Bot,
     chat_id: ChatId,
     generator: Arc<dyn ContentGenerator>,
     store: Arc<dyn MessageStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
 ) -> Result<(), ApiError> {
-    let text = if let Some(time_left) = get_time_until_friday() {
-        format!(
-            "До нефорской пятницы осталось: {} 🕷️ Готовь свой лучший аутфит. ⛓️",
-            format_time_delta(time_left)
-        )
-    } else {
-        String::from("SLAAAAAY! 💅🔥🖤 ЭТО НЕФОРСКАЯ ПЯТНИЦА, ДЕТКА! 🤘😈⛓️ Время сиять! ✨")
-    };
+    let settings = settings_store.get_settings(chat_id).await?;
+
+    let status = get_friday_status(&settings, Utc::now());
+    let text = friday_text(status);
+    let request = generation_request(&bot, &settings, prompts.as_ref(), status).await?;
+
+    let placeholder = bot.send_message(chat_id, STREAM_PLACEHOLDER).await?;
+    let result = with_chat_action(
+        &bot,
+        chat_id,
+        ChatAction::Typing,
+        generate_into_message(
+            &bot,
+            chat_id,
+            placeholder.id,
+            generator.as_ref(),
+            &request,
+            None,
+        ),
+    )
+    .await;
 
-    match generator.generate_text(text.as_str()).await {
-        Ok((new_text, model_name)) => {
-            store
-                .add_message(HistoryEntry::new(model_name, new_text.clone()))
-                .await;
+    match result {
+        Ok(generation) => {
+            show_generation(&bot, chat_id, placeholder.id, &generation.text).await?;
 
-            bot.send_message(chat_id, new_text).await?;
+            let entry = HistoryEntry::new(chat_id, placeholder.id, &request, &generation);
+            if let Err(e) = store.add_message(&entry).await {
+                error!(error = %e, "Failed to save generation history");
+            }
         }
         Err(err) => {
             error!(error = %err, "Failed to rephrase text");
-            bot.send_message(chat_id, text).await?;
+            bot.edit_message_text(chat_id, placeholder.id, text).await?;
         }
     }
 
     Ok(())
 }
+
+/// Builds the request for the chat persona, the template can still be previewed by name.
+pub async fn generation_request(
+    bot: &Bot,
+    settings: &ChatSettings,
+    prompts: &dyn PromptTemplateStore,
+    status: FridayStatus,
+) -> Result<GenerationRequest, ApiError> {
+    let name = settings
+        .prompt_template
+        .as_deref()
+        .unwrap_or(DEFAULT_PROMPT_TEMPLATE);
+
+    let template = match find_template(prompts, name).await? {
+        Some(template) => template,
+        None => {
+            warn!(
+                template = name,
+                "Active prompt template not found, using built-in"
+            );
+            PromptTemplate::builtin()
+        }
+    };
+
+    Ok(render_request(bot, settings.chat_id, &template, status).await)
+}
+
+pub async fn render_request(
+    bot: &Bot,
+    chat_id: ChatId,
+    template: &PromptTemplate,
+    status: FridayStatus,
+) -> GenerationRequest {
+    let time_left = match status {
+        FridayStatus::Countdown(time_left) => format_time_delta(time_left),
+        FridayStatus::Started | FridayStatus::Over => String::new(),
+    };
+
+    // Only ask Telegram for the title when the template actually needs it
+    let chat_title = if template.uses(CHAT_TITLE_VARIABLE) {
+        chat_title(bot, chat_id).await
+    } else {
+        String::new()
+    };
+
+    let key = cache_key(chat_id, template, status, &time_left);
+    let variables = PromptVariables {
+        time_left,
+        chat_title,
+    };
+
+    GenerationRequest::new(
+        template.name.clone(),
+        template.render(&variables),
+        friday_text(status),
+    )
+    .with_cache_key(key)
+}
+
+/// Requests of a chat persona share answers while the countdown in the prompt reads the same,
+/// so a cached text never shows a stale number.
+fn cache_key(
+    chat_id: ChatId,
+    template: &PromptTemplate,
+    status: FridayStatus,
+    time_left: &str,
+) -> String {
+    let bucket = match status {
+        FridayStatus::Countdown(_) => time_left,
+        FridayStatus::Started => "started",
+        FridayStatus::Over => "over",
+    };
+
+    format!("{}:{}:{}", chat_id, template.name, bucket)
+}
+
+/// Looks the template up in the storage, the built-in one is always available by its name.
+pub async fn find_template(
+    prompts: &dyn PromptTemplateStore,
+    name: &str,
+) -> Result<Option<PromptTemplate>, ApiError> {
+    if let Some(template) = prompts.get_template(name).await? {
+        return Ok(Some(template));
+    }
+
+    Ok((name == DEFAULT_PROMPT_TEMPLATE).then(PromptTemplate::builtin))
+}
+
+async fn chat_title(bot: &Bot, chat_id: ChatId) -> String {
+    match bot.get_chat(chat_id).await {
+        Ok(chat) => chat
+            .title()
+            .or(chat.first_name())
+            .unwrap_or_default()
+            .to_string(),
+        Err(e) => {
+            warn!(error = %e, "Failed to get chat title");
+            String::new()
+        }
+    }
+}
+
+pub fn friday_text(status: FridayStatus) -> String {
+    match status {
+        FridayStatus::Countdown(time_left) => format!(
+            "До нефорской пятницы осталось: {} 🕷️ Готовь свой лучший аутфит. ⛓️",
+            format_time_delta(time_left)
+        ),
+        FridayStatus::Started => {
+            String::from("SLAAAAAY! 💅🔥🖤 ЭТО НЕФОРСКАЯ ПЯТНИЦА, ДЕТКА! 🤘😈⛓️ Время сиять! ✨")
+        }
+        FridayStatus::Over => String::from(
+            "Нефорская пятница на сегодня закончилась 🖤 Отдыхай, копи силы до следующей! ⚰️",
+        ),
+    }
+}
+
+#[test]
+fn cache_key_test() {
+    use chrono::Duration;
+
+    let template = PromptTemplate::builtin();
+    let key = |status| {
+        let time_left = match status {
+            FridayStatus::Countdown(time_left) => format_time_delta(time_left),
+            _ => String::new(),
+        };
+        cache_key(ChatId(42), &template, status, &time_left)
+    };
+
+    // In the last hour the countdown shows seconds, a second later is another answer
+    let soon = FridayStatus::Countdown(Duration::seconds(30 * 60 + 15));
+    let later = FridayStatus::Countdown(Duration::seconds(30 * 60 + 14));
+    assert_eq!(key(soon), "42:нефор:30 минут, 15 секунд");
+    assert_ne!(key(soon), key(later));
+
+    let far = FridayStatus::Countdown(Duration::seconds(2 * 3600 + 15));
+    let far_later = FridayStatus::Countdown(Duration::seconds(2 * 3600 + 14));
+    assert_eq!(key(far), key(far_later));
+
+    assert_eq!(key(FridayStatus::Started), "42:нефор:started");
+}
@@ -0,0 +1,226 @@
+use crate::errors::ApiError;
+use crate::errors::ApiError::CommandConversionError;
+use crate::handlers::chat_action::with_chat_action;
+use crate::handlers::friday::generation_request;
+use crate::handlers::root_handler::{
+    ChatSettingsStore, ContentGenerator, MessageStore, PromptTemplateStore, VoteStore,
+};
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
+use crate::repo::vote_storage_postgres::dto::{Vote, VoteValue};
+use crate::utils::get_friday_status;
+use chrono::Utc;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{
+    ChatAction, InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ReplyParameters,
+};
+use teloxide::{ApiError as TelegramApiError, Bot, RequestError};
+use tracing::{error, info, instrument, warn};
+
+/// Prefix keeping these callbacks apart from the /slay menu ones, which are command names.
+const CALLBACK_PREFIX: &str = "gen:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationAction {
+    Regenerate,
+    Upvote,
+    Downvote,
+}
+
+impl GenerationAction {
+    fn label(self) -> &'static str {
+        match self {
+            GenerationAction::Regenerate => "🔁 regenerate",
+            GenerationAction::Upvote => "🔥",
+            GenerationAction::Downvote => "💩",
+        }
+    }
+}
+
+impl Display for GenerationAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let action = match self {
+            GenerationAction::Regenerate => "regenerate",
+            GenerationAction::Upvote => "up",
+            GenerationAction::Downvote => "down",
+        };
+
+        write!(f, "{}{}", CALLBACK_PREFIX, action)
+    }
+}
+
+impl FromStr for GenerationAction {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix(CALLBACK_PREFIX) {
+            Some("regenerate") => Ok(GenerationAction::Regenerate),
+            Some("up") => Ok(GenerationAction::Upvote),
+            Some("down") => Ok(GenerationAction::Downvote),
+            _ => Err(CommandConversionError(format!(
+                "Unknown generation action: {}",
+                s
+            ))),
+        }
+    }
+}
+
+pub fn generation_keyboard() -> InlineKeyboardMarkup {
+    let buttons = [
+        GenerationAction::Regenerate,
+        GenerationAction::Upvote,
+        GenerationAction::Downvote,
+    ]
+    .map(|action| InlineKeyboardButton::callback(action.label(), action.to_string()));
+
+    InlineKeyboardMarkup::new([buttons])
+}
+
+/// Puts the final text with the buttons into the message, it may already show the same text.
+pub async fn show_generation(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: &str,
+) -> Result<(), ApiError> {
+    let result = bot
+        .edit_message_text(chat_id, message_id, text)
+        .reply_markup(generation_keyboard())
+        .await;
+
+    match result {
+        Ok(_) | Err(RequestError::Api(TelegramApiError::MessageNotModified)) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(bot, q, generator, message_store, settings_store, prompts, votes))]
+pub async fn generation_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    action: GenerationAction,
+    generator: Arc<dyn ContentGenerator>,
+    message_store: Arc<dyn MessageStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    votes: Arc<dyn VoteStore>,
+) -> Result<(), ApiError> {
+    let Some(message) = q.message.as_ref() else {
+        warn!("generation callback without message");
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    let chat_id = message.chat().id;
+    let message_id = message.id();
+    let previous = message_store.get_message_info(chat_id, message_id).await?;
+
+    match action {
+        GenerationAction::Regenerate => {
+            bot.answer_callback_query(q.id.clone())
+                .text("Генерирую заново...")
+                .await?;
+
+            regenerate(
+                bot,
+                chat_id,
+                message_id,
+                previous,
+                generator,
+                message_store,
+                settings_store,
+                prompts,
+            )
+            .await?
+        }
+
+        GenerationAction::Upvote | GenerationAction::Downvote => {
+            let Some(entry) = previous else {
+                bot.answer_callback_query(q.id.clone())
+                    .text("Информации про это сообщение не найдено")
+                    .await?;
+                return Ok(());
+            };
+
+            let value = if action == GenerationAction::Upvote {
+                VoteValue::Up
+            } else {
+                VoteValue::Down
+            };
+
+            votes.vote(&Vote::new(&entry, q.from.id, value)).await?;
+            info!(model = %entry.model, ?value, "Generation voted");
+
+            bot.answer_callback_query(q.id.clone())
+                .text(format!("Голос за {} учтен", entry.model))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn regenerate(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    previous: Option<HistoryEntry>,
+    generator: Arc<dyn ContentGenerator>,
+    message_store: Arc<dyn MessageStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
+) -> Result<(), ApiError> {
+    let settings = settings_store.get_settings(chat_id).await?;
+    let status = get_friday_status(&settings, Utc::now());
+    let request = generation_request(&bot, &settings, prompts.as_ref(), status).await?;
+
+    let result = with_chat_action(&bot, chat_id, ChatAction::Typing, async {
+        match &previous {
+            Some(entry) => generator.regenerate_text(&request, &entry.model).await,
+            None => generator.generate_text(&request).await,
+        }
+    })
+    .await;
+
+    let generation = match result {
+        Ok(generation) => generation,
+        Err(e) => {
+            error!(error = %e, "Failed to regenerate text");
+            // The callback is already answered, so the notice goes to the chat
+            bot.send_message(chat_id, "Не удалось перегенерировать текст, попробуй позже")
+                .reply_parameters(ReplyParameters::new(message_id))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    show_generation(&bot, chat_id, message_id, &generation.text).await?;
+
+    let entry = HistoryEntry::new(chat_id, message_id, &request, &generation);
+    if let Err(e) = message_store.add_message(&entry).await {
+        error!(error = %e, "Failed to save generation history");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generation_action_callback_data_test() {
+    for action in [
+        GenerationAction::Regenerate,
+        GenerationAction::Upvote,
+        GenerationAction::Downvote,
+    ] {
+        assert_eq!(
+            action.to_string().parse::<GenerationAction>().ok(),
+            Some(action)
+        );
+    }
+
+    assert!("/friday".parse::<GenerationAction>().is_err());
+    assert!("gen:unknown".parse::<GenerationAction>().is_err());
+}
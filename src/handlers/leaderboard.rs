@@ -0,0 +1,173 @@
+use crate::errors::ApiError;
+use crate::handlers::root_handler::ContentGenerator;
+use crate::repo::model_stats_storage_postgres::dto::ModelLeaderboardEntry;
+use chrono::{TimeDelta, Utc};
+use std::cmp::Ordering;
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use tracing::{error, instrument};
+
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+/// Attempts are pruned by retention long before that, it only keeps the date arithmetic sane.
+const MAX_WINDOW_DAYS: i64 = 10 * 365;
+
+const WINDOW_USAGE: &str = "Окно задается числом и единицей: h — часы, d — дни, w — недели.
+Например, /models 24h или /models 4w";
+
+#[instrument(skip(bot, generator))]
+pub async fn leaderboard(
+    bot: Bot,
+    chat_id: ChatId,
+    args: String,
+    generator: Arc<dyn ContentGenerator>,
+) -> Result<(), ApiError> {
+    let Some((window, label)) = parse_window(&args) else {
+        bot.send_message(chat_id, WINDOW_USAGE).await?;
+        return Ok(());
+    };
+
+    let Some(since) = Utc::now().checked_sub_signed(window) else {
+        bot.send_message(chat_id, WINDOW_USAGE).await?;
+        return Ok(());
+    };
+
+    let mut entries = match generator.leaderboard(since).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!(error = %e, "Failed to load model leaderboard");
+            bot.send_message(chat_id, "Не удалось получить статистику моделей")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if entries.is_empty() {
+        bot.send_message(chat_id, "Нет подключенных моделей")
+            .await?;
+        return Ok(());
+    }
+
+    entries.sort_by(rank);
+
+    let lines: Vec<String> = entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| describe_entry(idx + 1, entry))
+        .collect();
+
+    bot.send_message(
+        chat_id,
+        format!("Рейтинг моделей за {}:\n\n{}", label, lines.join("\n\n")),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Votes first, reliability breaks ties.
+fn rank(a: &ModelLeaderboardEntry, b: &ModelLeaderboardEntry) -> Ordering {
+    b.vote_score.cmp(&a.vote_score).then_with(|| {
+        let a_rate = a.success_rate().unwrap_or(-1.0);
+        let b_rate = b.success_rate().unwrap_or(-1.0);
+        b_rate.total_cmp(&a_rate)
+    })
+}
+
+fn describe_entry(place: usize, entry: &ModelLeaderboardEntry) -> String {
+    let percent = |rate: Option<f64>| {
+        rate.map(|r| format!("{:.0}%", r * 100.0))
+            .unwrap_or_else(|| String::from("нет данных"))
+    };
+    let latency = entry
+        .avg_latency
+        .map(|l| format!("{:.1} с", l.as_secs_f64()))
+        .unwrap_or_else(|| String::from("нет данных"));
+
+    format!(
+        "{}. {} — голоса: {:+}\nгенераций: {}, успешных: {}, среднее время: {}, невалидных ответов: {}",
+        place,
+        entry.model,
+        entry.vote_score,
+        entry.generations,
+        percent(entry.success_rate()),
+        latency,
+        percent(entry.validation_failure_rate()),
+    )
+}
+
+/// Parses "24h", "7d" or "4w"; an empty argument means the last week.
+fn parse_window(args: &str) -> Option<(TimeDelta, String)> {
+    let args = args.trim();
+    if args.is_empty() {
+        return Some((
+            TimeDelta::days(DEFAULT_WINDOW_DAYS),
+            format!("{} дней", DEFAULT_WINDOW_DAYS),
+        ));
+    }
+
+    let unit = args.chars().last()?;
+    let amount: i64 = args[..args.len() - unit.len_utf8()].parse().ok()?;
+    if amount <= 0 {
+        return None;
+    }
+
+    let (window, unit_label) = match unit.to_ascii_lowercase() {
+        'h' => (TimeDelta::try_hours(amount)?, "ч"),
+        'd' => (TimeDelta::try_days(amount)?, "д"),
+        'w' => (TimeDelta::try_weeks(amount)?, "нед"),
+        _ => return None,
+    };
+
+    if window > TimeDelta::days(MAX_WINDOW_DAYS) {
+        return None;
+    }
+
+    Some((window, format!("{} {}", amount, unit_label)))
+}
+
+#[test]
+fn parse_window_test() {
+    assert_eq!(
+        parse_window(""),
+        Some((TimeDelta::days(7), String::from("7 дней")))
+    );
+    assert_eq!(
+        parse_window("24h"),
+        Some((TimeDelta::hours(24), String::from("24 ч")))
+    );
+    assert_eq!(
+        parse_window(" 4W "),
+        Some((TimeDelta::weeks(4), String::from("4 нед")))
+    );
+    assert_eq!(parse_window("0d"), None);
+    assert_eq!(parse_window("d"), None);
+    assert_eq!(parse_window("7m"), None);
+    assert_eq!(parse_window("неделя"), None);
+    assert_eq!(parse_window("100000000w"), None);
+    assert_eq!(parse_window("9223372036854775807h"), None);
+    assert!(parse_window("520w").is_some());
+}
+
+#[test]
+fn leaderboard_rank_test() {
+    use crate::common::Model;
+
+    let entry = |name: &str, generations, vote_score| ModelLeaderboardEntry {
+        attempts: 10,
+        generations,
+        vote_score,
+        ..ModelLeaderboardEntry::empty(Model::new(name))
+    };
+
+    let mut entries = [
+        ModelLeaderboardEntry::empty(Model::new("Gigachat")),
+        entry("Grok", 5, 2),
+        entry("Mistral", 9, 2),
+        entry("Gemini", 10, 3),
+    ];
+    entries.sort_by(rank);
+
+    let names: Vec<String> = entries.iter().map(|e| e.model.to_string()).collect();
+    assert_eq!(names, ["Gemini", "Mistral", "Grok", "Gigachat"]);
+}
@@ -1,11 +1,21 @@
 pub mod add_media;
+pub mod admin;
+mod chat_action;
+pub mod conversation;
 mod delete_media;
-mod friday;
+pub mod friday;
+pub mod generation_buttons;
 mod get_media;
+mod leaderboard;
 mod list_available_media;
 mod model_info;
+pub mod pinned_countdown;
+mod prompt_templates;
 pub mod rename_media;
 pub mod root_handler;
+mod settings;
 pub mod slay;
 pub mod state_dispatcher;
+mod streaming;
+mod subscription;
 mod utils;
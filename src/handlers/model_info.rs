@@ -1,14 +1,19 @@
 use crate::errors::ApiError;
-use crate::handlers::root_handler::MessageStore;
+use crate::handlers::root_handler::{ChatSettingsStore, MessageStore};
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
+use chrono_tz::Tz;
 use log::debug;
 use std::sync::Arc;
 use teloxide::Bot;
 use teloxide::prelude::*;
 
+const GENERATED_AT_FORMAT: &str = "%d.%m.%Y %H:%M:%S";
+
 pub async fn model_info(
     bot: Bot,
     msg: Message,
     store: Arc<dyn MessageStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
 ) -> Result<(), ApiError> {
     let reply_msg = match msg.reply_to_message() {
         Some(m) => m,
@@ -19,22 +24,12 @@ pub async fn model_info(
         }
     };
 
-    let text = match reply_msg.text() {
-        Some(t) => t,
-        None => {
-            bot.send_message(msg.chat.id, "Это сообщение не сгенерировано ботом")
-                .await?;
-            return Ok(());
-        }
-    };
+    match store.get_message_info(msg.chat.id, reply_msg.id).await? {
+        Some(entry) => {
+            let settings = settings_store.get_settings(msg.chat.id).await?;
 
-    match store.get_message_info(text).await {
-        Some(model) => {
-            bot.send_message(
-                msg.chat.id,
-                format!("Это сообщение сгенерировано: {}", model),
-            )
-            .await?;
+            bot.send_message(msg.chat.id, describe_entry(&entry, settings.timezone))
+                .await?;
         }
         None => {
             debug!("No entry found in storage");
@@ -45,3 +40,76 @@ pub async fn model_info(
 
     Ok(())
 }
+
+fn describe_entry(entry: &HistoryEntry, timezone: Tz) -> String {
+    let mut description = format!(
+        "Это сообщение сгенерировано: {}\nВремя ответа модели: {:.1} с\nШаблон промпта: {}",
+        entry.model,
+        entry.latency.as_secs_f64(),
+        entry.prompt_template,
+    );
+
+    if let Some(usage) = entry.usage {
+        description.push_str(&format!(
+            "\nТокены: {} на запрос, {} на ответ",
+            usage.prompt_tokens, usage.completion_tokens
+        ));
+    }
+
+    if let Some(cost) = entry.cost.filter(|cost| *cost > 0.0) {
+        description.push_str(&format!("\nСтоимость: {:.6}", cost));
+    }
+
+    description.push_str(&format!(
+        "\nСгенерировано: {}",
+        entry
+            .created_at
+            .with_timezone(&timezone)
+            .format(GENERATED_AT_FORMAT)
+    ));
+
+    description
+}
+
+#[test]
+fn describe_entry_test() {
+    use crate::common::Model;
+    use crate::generation_controller::TokenUsage;
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+    use teloxide::types::MessageId;
+    use uuid::Uuid;
+
+    let entry = HistoryEntry {
+        id: Uuid::new_v4(),
+        chat_id: ChatId(1),
+        message_id: MessageId(42),
+        model: Model::new("Mistral"),
+        prompt_template: "нефор".to_string(),
+        input_text: "До нефорской пятницы осталось: 2 часа".to_string(),
+        output_text: "Нефорская пятница через 2 часа".to_string(),
+        latency: Duration::from_millis(1530),
+        usage: None,
+        cost: None,
+        created_at: Utc.with_ymd_and_hms(2026, 10, 16, 7, 0, 5).unwrap(),
+    };
+
+    assert_eq!(
+        describe_entry(&entry, chrono_tz::Europe::Moscow),
+        "Это сообщение сгенерировано: Mistral\nВремя ответа модели: 1.5 с\nШаблон промпта: нефор\nСгенерировано: 16.10.2026 10:00:05"
+    );
+
+    let entry = HistoryEntry {
+        usage: Some(TokenUsage {
+            prompt_tokens: 120,
+            completion_tokens: 35,
+        }),
+        cost: Some(0.000045),
+        ..entry
+    };
+
+    assert_eq!(
+        describe_entry(&entry, chrono_tz::Europe::Moscow),
+        "Это сообщение сгенерировано: Mistral\nВремя ответа модели: 1.5 с\nШаблон промпта: нефор\nТокены: 120 на запрос, 35 на ответ\nСтоимость: 0.000045\nСгенерировано: 16.10.2026 10:00:05"
+    );
+}
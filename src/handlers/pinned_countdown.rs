@@ -0,0 +1,87 @@
+use crate::errors::ApiError;
+use crate::formatting::format_time_delta_minutes;
+use crate::handlers::root_handler::{ChatSettingsStore, PinnedCountdownStore};
+use crate::utils::{FridayStatus, get_friday_status};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use tracing::{error, instrument, warn};
+
+#[instrument(skip(bot, settings_store, pinned_store))]
+pub async fn pin_countdown(
+    bot: Bot,
+    chat_id: ChatId,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    pinned_store: Arc<dyn PinnedCountdownStore>,
+) -> Result<(), ApiError> {
+    if pinned_store.get_pinned(chat_id).await?.is_some() {
+        bot.send_message(chat_id, "Отсчет уже закреплен в этом чате")
+            .await?;
+        return Ok(());
+    }
+
+    let settings = settings_store.get_settings(chat_id).await?;
+    let FridayStatus::Countdown(time_left) = get_friday_status(&settings, Utc::now()) else {
+        bot.send_message(
+            chat_id,
+            "Нефорская пятница уже наступила, считать нечего 🖤",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let message = bot
+        .send_message(chat_id, pinned_countdown_text(time_left))
+        .await?;
+
+    if let Err(e) = bot
+        .pin_chat_message(chat_id, message.id)
+        .disable_notification(true)
+        .await
+    {
+        error!(error = %e, "Failed to pin countdown message");
+        bot.send_message(
+            chat_id,
+            "Не удалось закрепить сообщение, проверьте права бота",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    pinned_store.save_pinned(chat_id, message.id).await?;
+
+    Ok(())
+}
+
+#[instrument(skip(bot, pinned_store))]
+pub async fn unpin_countdown(
+    bot: Bot,
+    chat_id: ChatId,
+    pinned_store: Arc<dyn PinnedCountdownStore>,
+) -> Result<(), ApiError> {
+    let Some(message_id) = pinned_store.remove_pinned(chat_id).await? else {
+        bot.send_message(chat_id, "В этом чате нет закрепленного отсчета")
+            .await?;
+        return Ok(());
+    };
+
+    if let Err(e) = bot.unpin_chat_message(chat_id).message_id(message_id).await {
+        warn!(error = %e, "Failed to unpin countdown message");
+    }
+
+    bot.send_message(chat_id, "Закрепленный отсчет остановлен")
+        .await?;
+
+    Ok(())
+}
+
+pub fn pinned_countdown_text(time_left: Duration) -> String {
+    // Rounded to the nearest minute, since the message is edited at most once a minute
+    let time_left = time_left + Duration::seconds(30);
+
+    format!(
+        "⏳ До нефорской пятницы осталось: {} 🕷️",
+        format_time_delta_minutes(time_left)
+    )
+}
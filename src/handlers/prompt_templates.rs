@@ -0,0 +1,210 @@
+use crate::errors::ApiError;
+use crate::handlers::chat_action::with_chat_action;
+use crate::handlers::friday::{find_template, render_request};
+use crate::handlers::root_handler::{ChatSettingsStore, ContentGenerator, PromptTemplateStore};
+use crate::repo::prompt_template_storage_postgres::dto::{DEFAULT_PROMPT_TEMPLATE, PromptTemplate};
+use crate::utils::get_friday_status;
+use chrono::Utc;
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use teloxide::types::ChatAction;
+use tracing::{error, info, instrument};
+
+const TEMPLATE_USAGE: &str = "Шаблон задается так:
+/prompt_create название текст промпта
+
+В тексте можно использовать переменные:
+{time_left} — сколько осталось до пятницы
+{chat_title} — название чата";
+
+#[instrument(skip(bot, prompts, settings_store))]
+pub async fn list_templates(
+    bot: Bot,
+    chat_id: ChatId,
+    prompts: Arc<dyn PromptTemplateStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+) -> Result<(), ApiError> {
+    let settings = settings_store.get_settings(chat_id).await?;
+    let active = settings
+        .prompt_template
+        .as_deref()
+        .unwrap_or(DEFAULT_PROMPT_TEMPLATE);
+
+    let mut names = vec![DEFAULT_PROMPT_TEMPLATE.to_string()];
+    names.extend(
+        prompts
+            .list_templates()
+            .await?
+            .into_iter()
+            .map(|t| t.name)
+            .filter(|name| name != DEFAULT_PROMPT_TEMPLATE),
+    );
+
+    let lines: Vec<String> = names
+        .iter()
+        .map(|name| {
+            if name == active {
+                format!("• {} (активен)", name)
+            } else {
+                format!("• {}", name)
+            }
+        })
+        .collect();
+
+    bot.send_message(chat_id, format!("Шаблоны промптов:\n{}", lines.join("\n")))
+        .await?;
+
+    Ok(())
+}
+
+#[instrument(skip(bot, args, prompts))]
+pub async fn create_template(
+    bot: Bot,
+    msg: &Message,
+    args: String,
+    prompts: Arc<dyn PromptTemplateStore>,
+) -> Result<(), ApiError> {
+    let Some(template) = parse_template(&args) else {
+        bot.send_message(msg.chat.id, TEMPLATE_USAGE).await?;
+        return Ok(());
+    };
+
+    let user_id = msg.from.as_ref().map(|u| u.id);
+    if prompts.create_template(&template, user_id).await? {
+        info!(template = %template.name, "Prompt template created");
+        bot.send_message(msg.chat.id, format!("Шаблон «{}» создан", template.name))
+            .await?;
+    } else {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "Шаблон «{}» уже существует, используй /prompt_edit",
+                template.name
+            ),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(bot, args, prompts))]
+pub async fn edit_template(
+    bot: Bot,
+    chat_id: ChatId,
+    args: String,
+    prompts: Arc<dyn PromptTemplateStore>,
+) -> Result<(), ApiError> {
+    let Some(template) = parse_template(&args) else {
+        bot.send_message(chat_id, TEMPLATE_USAGE).await?;
+        return Ok(());
+    };
+
+    if prompts.update_template(&template).await? {
+        info!(template = %template.name, "Prompt template updated");
+        bot.send_message(chat_id, format!("Шаблон «{}» обновлен", template.name))
+            .await?;
+    } else {
+        bot.send_message(chat_id, format!("Шаблон «{}» не найден", template.name))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(bot, generator, prompts, settings_store))]
+pub async fn preview_template(
+    bot: Bot,
+    chat_id: ChatId,
+    name: String,
+    generator: Arc<dyn ContentGenerator>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+) -> Result<(), ApiError> {
+    let Some(template) = find_template(prompts.as_ref(), name.trim()).await? else {
+        bot.send_message(chat_id, format!("Шаблон «{}» не найден", name.trim()))
+            .await?;
+        return Ok(());
+    };
+
+    let settings = settings_store.get_settings(chat_id).await?;
+    let status = get_friday_status(&settings, Utc::now());
+    let request = render_request(&bot, chat_id, &template, status).await;
+
+    bot.send_message(
+        chat_id,
+        format!("Шаблон «{}»:\n\n{}", template.name, request.system_prompt),
+    )
+    .await?;
+
+    let result = with_chat_action(
+        &bot,
+        chat_id,
+        ChatAction::Typing,
+        generator.generate_text(&request),
+    )
+    .await;
+
+    let sample = match result {
+        Ok(generation) => format!("Пример от {}:\n\n{}", generation.model, generation.text),
+        Err(e) => {
+            error!(error = %e, "Failed to generate preview");
+            String::from("Не удалось сгенерировать пример")
+        }
+    };
+
+    bot.send_message(chat_id, sample).await?;
+
+    Ok(())
+}
+
+#[instrument(skip(bot, prompts, settings_store))]
+pub async fn activate_template(
+    bot: Bot,
+    chat_id: ChatId,
+    name: String,
+    prompts: Arc<dyn PromptTemplateStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+) -> Result<(), ApiError> {
+    let Some(template) = find_template(prompts.as_ref(), name.trim()).await? else {
+        bot.send_message(chat_id, format!("Шаблон «{}» не найден", name.trim()))
+            .await?;
+        return Ok(());
+    };
+
+    let mut settings = settings_store.get_settings(chat_id).await?;
+    settings.prompt_template = Some(template.name.clone());
+    settings_store.save_settings(&settings).await?;
+
+    info!(template = %template.name, "Prompt template activated");
+    bot.send_message(
+        chat_id,
+        format!("Теперь в этом чате используется шаблон «{}»", template.name),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Splits "name body..." keeping line breaks of the body intact.
+fn parse_template(args: &str) -> Option<PromptTemplate> {
+    let (name, body) = args.trim().split_once(char::is_whitespace)?;
+    let body = body.trim();
+
+    if body.is_empty() {
+        return None;
+    }
+
+    Some(PromptTemplate::new(name, body))
+}
+
+#[test]
+fn parse_template_test() {
+    assert_eq!(
+        parse_template("офисный Ты менеджер.\nПиши кратко."),
+        Some(PromptTemplate::new("офисный", "Ты менеджер.\nПиши кратко."))
+    );
+    assert_eq!(parse_template("офисный"), None);
+    assert_eq!(parse_template("офисный   "), None);
+}
@@ -1,35 +1,75 @@
 use crate::commands::Command;
 use crate::common::Model;
 use crate::errors::ApiError;
+use crate::generation_controller::{Generation, GenerationRequest, ModelHealth, TextProgress};
 use crate::handlers::add_media::trigger_add;
 use crate::handlers::delete_media::trigger_delete;
 use crate::handlers::friday::friday;
 use crate::handlers::get_media::get_media;
+use crate::handlers::leaderboard::leaderboard;
 use crate::handlers::list_available_media::list_default;
 use crate::handlers::model_info::model_info;
+use crate::handlers::pinned_countdown::{pin_countdown, unpin_countdown};
 use crate::handlers::rename_media::trigger_rename;
+use crate::handlers::settings::settings;
 use crate::handlers::slay::slay;
+use crate::handlers::subscription::{subscribe, unsubscribe};
+use crate::repo::chat_history_storage::ChatLine;
+use crate::repo::chat_settings_storage_postgres::dto::ChatSettings;
 use crate::repo::dialogue_storage::DialogueStorageKey;
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
 use crate::repo::media_storage_postgres::dto::MediaEntry;
-use crate::repo::message_history_storage::HistoryEntry;
+use crate::repo::model_stats_storage_postgres::dto::{
+    GenerationAttempt, ModelLeaderboardEntry, ModelSpending,
+};
+use crate::repo::prompt_template_storage_postgres::dto::PromptTemplate;
+use crate::repo::vote_storage_postgres::dto::Vote;
 use crate::states::State;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use teloxide::Bot;
 use teloxide::prelude::{Message, Requester, UserId};
-use teloxide::types::ChatId;
+use teloxide::types::{ChatId, MessageId};
 use teloxide::utils::command::BotCommands;
 use tracing::instrument;
 
 #[async_trait]
 pub trait ContentGenerator: Send + Sync {
-    async fn generate_text(&self, current_text: &str) -> Result<(String, Model), ApiError>;
+    async fn generate_text(&self, request: &GenerationRequest) -> Result<Generation, ApiError>;
+    /// Generates a new answer, preferring a model other than the `previous` one.
+    async fn regenerate_text(
+        &self,
+        request: &GenerationRequest,
+        previous: &Model,
+    ) -> Result<Generation, ApiError>;
+    /// Same as the above, publishing the text to `progress` while it is generated.
+    async fn generate_streaming(
+        &self,
+        request: &GenerationRequest,
+        previous: Option<&Model>,
+        progress: &TextProgress,
+    ) -> Result<Generation, ApiError>;
+    /// Prepares answers for the key of the request in advance, returns how many were added.
+    async fn pregenerate(&self, request: &GenerationRequest) -> Result<usize, ApiError>;
+
+    fn model_health(&self) -> Vec<ModelHealth>;
+    /// Persisted statistics for every model of the pool since the given moment.
+    async fn leaderboard(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ModelLeaderboardEntry>, ApiError>;
 }
 
 #[async_trait]
 pub trait MessageStore: Send + Sync {
-    async fn add_message(&self, message: HistoryEntry);
-    async fn get_message_info(&self, message: &str) -> Option<Model>;
+    async fn add_message(&self, entry: &HistoryEntry) -> Result<(), ApiError>;
+    async fn get_message_info(
+        &self,
+        chat_id: ChatId,
+        message_id: MessageId,
+    ) -> Result<Option<HistoryEntry>, ApiError>;
+    async fn remove_older_than(&self, cutoff: DateTime<Utc>) -> Result<u64, ApiError>;
 }
 
 #[async_trait]
@@ -55,13 +95,81 @@ pub trait MediaStore: Send + Sync {
     async fn is_already_created(&self, media_entry_name: &str) -> Result<bool, ApiError>;
 }
 
+#[async_trait]
+pub trait SubscriptionStore: Send + Sync {
+    async fn subscribe(&self, chat_id: ChatId, user_id: Option<UserId>) -> Result<bool, ApiError>;
+    async fn unsubscribe(&self, chat_id: ChatId) -> Result<bool, ApiError>;
+    async fn list_subscribed_chats(&self) -> Result<Vec<ChatId>, ApiError>;
+}
+
+#[async_trait]
+pub trait ChatSettingsStore: Send + Sync {
+    async fn get_settings(&self, chat_id: ChatId) -> Result<ChatSettings, ApiError>;
+    async fn save_settings(&self, settings: &ChatSettings) -> Result<(), ApiError>;
+}
+
+#[async_trait]
+pub trait PinnedCountdownStore: Send + Sync {
+    async fn save_pinned(&self, chat_id: ChatId, message_id: MessageId) -> Result<(), ApiError>;
+    async fn get_pinned(&self, chat_id: ChatId) -> Result<Option<MessageId>, ApiError>;
+    async fn remove_pinned(&self, chat_id: ChatId) -> Result<Option<MessageId>, ApiError>;
+    async fn list_pinned(&self) -> Result<Vec<(ChatId, MessageId)>, ApiError>;
+}
+
+#[async_trait]
+pub trait PromptTemplateStore: Send + Sync {
+    async fn create_template(
+        &self,
+        template: &PromptTemplate,
+        user_id: Option<UserId>,
+    ) -> Result<bool, ApiError>;
+    async fn update_template(&self, template: &PromptTemplate) -> Result<bool, ApiError>;
+    async fn get_template(&self, name: &str) -> Result<Option<PromptTemplate>, ApiError>;
+    async fn list_templates(&self) -> Result<Vec<PromptTemplate>, ApiError>;
+}
+
+#[async_trait]
+pub trait ModelStatsStore: Send + Sync {
+    async fn record_attempt(&self, attempt: &GenerationAttempt) -> Result<(), ApiError>;
+    async fn leaderboard(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ModelLeaderboardEntry>, ApiError>;
+    async fn spending_since(&self, since: DateTime<Utc>) -> Result<Vec<ModelSpending>, ApiError>;
+    async fn remove_older_than(&self, cutoff: DateTime<Utc>) -> Result<u64, ApiError>;
+}
+
+#[async_trait]
+pub trait VoteStore: Send + Sync {
+    async fn vote(&self, vote: &Vote) -> Result<(), ApiError>;
+}
+
+pub trait ChatHistoryStore: Send + Sync {
+    fn remember(&self, chat_id: ChatId, line: ChatLine);
+    /// Remembered messages of the chat, oldest first.
+    fn recent(&self, chat_id: ChatId) -> Vec<ChatLine>;
+}
+
 pub trait DialogueStore: Send + Sync {
     fn get_dialogue(&self, key: &DialogueStorageKey) -> Option<State>;
     fn remove_dialogue(&self, key: &DialogueStorageKey) -> Option<(DialogueStorageKey, State)>;
     fn update_dialogue(&self, key: DialogueStorageKey, new_state: State) -> Option<State>;
 }
 
-#[instrument(skip(bot, generator, cmd, msg, media_store, message_store, dialogue))]
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(
+    bot,
+    generator,
+    cmd,
+    msg,
+    media_store,
+    message_store,
+    dialogue,
+    subscriptions,
+    settings_store,
+    pinned_store,
+    prompts
+))]
 pub async fn handle_command(
     bot: Bot,
     msg: Message,
@@ -70,13 +178,29 @@ pub async fn handle_command(
     media_store: Arc<dyn MediaStore>,
     message_store: Arc<dyn MessageStore>,
     dialogue: Arc<dyn DialogueStore>,
+    subscriptions: Arc<dyn SubscriptionStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    pinned_store: Arc<dyn PinnedCountdownStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
 ) -> Result<(), ApiError> {
     match cmd {
         Command::Help => help(bot, msg.chat.id).await?,
 
-        Command::Friday => friday(bot, msg.chat.id, generator, message_store).await?,
+        Command::Friday => {
+            friday(
+                bot,
+                msg.chat.id,
+                generator,
+                message_store,
+                settings_store,
+                prompts,
+            )
+            .await?
+        }
 
-        Command::Model => model_info(bot, msg, message_store).await?,
+        Command::Model => model_info(bot, msg, message_store, settings_store).await?,
+
+        Command::Models(args) => leaderboard(bot, msg.chat.id, args, generator).await?,
 
         Command::ListMedia => list_default(bot, msg.chat.id, media_store).await?,
 
@@ -90,6 +214,18 @@ pub async fn handle_command(
 
         Command::DeleteMedia => trigger_delete(bot, msg.chat.id, msg.from, dialogue).await?,
         Command::Slay => slay(bot, msg.chat.id, msg.from).await?,
+
+        Command::Subscribe => subscribe(bot, msg.chat.id, msg.from, subscriptions).await?,
+
+        Command::Unsubscribe => unsubscribe(bot, msg.chat.id, subscriptions).await?,
+
+        Command::Settings(args) => settings(bot, msg.chat.id, args, settings_store).await?,
+
+        Command::PinCountdown => {
+            pin_countdown(bot, msg.chat.id, settings_store, pinned_store).await?
+        }
+
+        Command::UnpinCountdown => unpin_countdown(bot, msg.chat.id, pinned_store).await?,
     }
 
     Ok(())
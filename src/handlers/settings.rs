@@ -0,0 +1,135 @@
+use crate::errors::ApiError;
+use crate::handlers::root_handler::ChatSettingsStore;
+use crate::repo::chat_settings_storage_postgres::dto::ChatSettings;
+use crate::repo::prompt_template_storage_postgres::dto::DEFAULT_PROMPT_TEMPLATE;
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use tracing::{error, instrument};
+
+const TIME_FORMAT: &str = "%H:%M";
+
+const SETTINGS_USAGE: &str = "Изменить настройки:
+/settings tz Europe/Moscow — часовой пояс
+/settings day fri — день недели
+/settings start 18:00 — время начала
+/settings end 23:59 — время окончания
+/settings replies on — отвечать на упоминания и ответы";
+
+#[instrument(skip(bot, settings_store))]
+pub async fn settings(
+    bot: Bot,
+    chat_id: ChatId,
+    args: String,
+    settings_store: Arc<dyn ChatSettingsStore>,
+) -> Result<(), ApiError> {
+    let mut settings = match settings_store.get_settings(chat_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to get chat settings");
+            bot.send_message(chat_id, "Не удалось получить настройки чата")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let mut args = args.split_whitespace();
+    let (Some(key), Some(value)) = (args.next(), args.next()) else {
+        bot.send_message(
+            chat_id,
+            format!("{}\n\n{}", describe_settings(&settings), SETTINGS_USAGE),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    if let Err(reason) = apply_setting(&mut settings, key, value) {
+        bot.send_message(chat_id, format!("{}\n\n{}", reason, SETTINGS_USAGE))
+            .await?;
+        return Ok(());
+    }
+
+    match settings_store.save_settings(&settings).await {
+        Ok(()) => {
+            bot.send_message(
+                chat_id,
+                format!("Настройки обновлены!\n\n{}", describe_settings(&settings)),
+            )
+            .await?;
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to save chat settings");
+            bot.send_message(chat_id, "Не удалось сохранить настройки чата")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_setting(settings: &mut ChatSettings, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "tz" | "timezone" => {
+            settings.timezone = value
+                .parse::<Tz>()
+                .map_err(|_| format!("Неизвестный часовой пояс: {}", value))?;
+        }
+        "day" | "weekday" => {
+            settings.weekday = value
+                .parse::<Weekday>()
+                .map_err(|_| format!("Неизвестный день недели: {}", value))?;
+        }
+        "start" => settings.start_time = parse_time(value)?,
+        "end" => settings.end_time = parse_time(value)?,
+        "replies" => settings.replies_enabled = parse_switch(value)?,
+        _ => return Err(format!("Неизвестная настройка: {}", key)),
+    }
+
+    Ok(())
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, TIME_FORMAT)
+        .map_err(|_| format!("Время должно быть в формате ЧЧ:ММ, получено: {}", value))
+}
+
+fn parse_switch(value: &str) -> Result<bool, String> {
+    match value {
+        "on" | "вкл" => Ok(true),
+        "off" | "выкл" => Ok(false),
+        _ => Err(format!("Ожидается on или off, получено: {}", value)),
+    }
+}
+
+fn describe_settings(settings: &ChatSettings) -> String {
+    format!(
+        "Настройки чата:\nЧасовой пояс: {}\nДень: {}\nНачало: {}\nОкончание: {}\nПерсона: {}\nОтветы на упоминания: {}",
+        settings.timezone.name(),
+        weekday_name(settings.weekday),
+        settings.start_time.format(TIME_FORMAT),
+        settings.end_time.format(TIME_FORMAT),
+        settings
+            .prompt_template
+            .as_deref()
+            .unwrap_or(DEFAULT_PROMPT_TEMPLATE),
+        if settings.replies_enabled {
+            "вкл"
+        } else {
+            "выкл"
+        }
+    )
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "понедельник",
+        Weekday::Tue => "вторник",
+        Weekday::Wed => "среда",
+        Weekday::Thu => "четверг",
+        Weekday::Fri => "пятница",
+        Weekday::Sat => "суббота",
+        Weekday::Sun => "воскресенье",
+    }
+}
@@ -4,9 +4,16 @@ use crate::errors::ApiError;
 use crate::handlers::add_media::trigger_add;
 use crate::handlers::delete_media::trigger_delete;
 use crate::handlers::friday::friday;
+use crate::handlers::leaderboard::leaderboard;
 use crate::handlers::list_available_media::list_default;
+use crate::handlers::pinned_countdown::{pin_countdown, unpin_countdown};
 use crate::handlers::rename_media::trigger_rename;
-use crate::handlers::root_handler::{ContentGenerator, DialogueStore, MessageStore, help};
+use crate::handlers::root_handler::{
+    ChatSettingsStore, ContentGenerator, DialogueStore, MessageStore, PinnedCountdownStore,
+    PromptTemplateStore, SubscriptionStore, help,
+};
+use crate::handlers::settings::settings;
+use crate::handlers::subscription::{subscribe, unsubscribe};
 use crate::handlers::utils::get_user_id_from_option;
 use crate::utils::{reply_suggestions_keyboard, setup_inline_callback_keyboard};
 use std::sync::Arc;
@@ -43,6 +50,7 @@ pub async fn slay(bot: Bot, chat_id: ChatId, from: Option<User>) -> Result<(), A
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn inline_choice_callback(
     bot: Bot,
     q: CallbackQuery,
@@ -50,6 +58,10 @@ pub async fn inline_choice_callback(
     message_store: Arc<dyn MessageStore>,
     media_store: Arc<dyn MediaStore>,
     dialogue: Arc<dyn DialogueStore>,
+    subscriptions: Arc<dyn SubscriptionStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    pinned_store: Arc<dyn PinnedCountdownStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
 ) -> Result<(), ApiError> {
     bot.answer_callback_query(q.id.clone()).await?;
 
@@ -72,7 +84,15 @@ pub async fn inline_choice_callback(
             Ok(())
         }
         Command::Friday => {
-            friday(bot, chat_id, generator, message_store).await?;
+            friday(
+                bot,
+                chat_id,
+                generator,
+                message_store,
+                settings_store,
+                prompts,
+            )
+            .await?;
             Ok(())
         }
         Command::ListMedia => {
@@ -121,6 +141,36 @@ pub async fn inline_choice_callback(
             trigger_delete(bot, chat_id, Some(q.from), dialogue).await?;
             Ok(())
         }
+
+        Command::Subscribe => {
+            subscribe(bot, chat_id, Some(q.from), subscriptions).await?;
+            Ok(())
+        }
+
+        Command::Unsubscribe => {
+            unsubscribe(bot, chat_id, subscriptions).await?;
+            Ok(())
+        }
+
+        Command::Models(_) => {
+            leaderboard(bot, chat_id, String::new(), generator).await?;
+            Ok(())
+        }
+
+        Command::Settings(_) => {
+            settings(bot, chat_id, String::new(), settings_store).await?;
+            Ok(())
+        }
+
+        Command::PinCountdown => {
+            pin_countdown(bot, chat_id, settings_store, pinned_store).await?;
+            Ok(())
+        }
+
+        Command::UnpinCountdown => {
+            unpin_countdown(bot, chat_id, pinned_store).await?;
+            Ok(())
+        }
         cmd => {
             bot.send_message(chat_id, format!("Команда {cmd} пока не поддерживается"))
                 .await?;
@@ -0,0 +1,122 @@
+use crate::common::Model;
+use crate::errors::ApiError;
+use crate::generation_controller::{Generation, GenerationRequest};
+use crate::handlers::root_handler::ContentGenerator;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::types::MessageId;
+use teloxide::{ApiError as TelegramApiError, Bot, RequestError};
+use tokio::sync::{oneshot, watch};
+use tracing::{debug, warn};
+
+/// Shown until the first part of the generation arrives.
+pub const STREAM_PLACEHOLDER: &str = "✍️ ...";
+
+/// Telegram allows about 20 messages per minute in a group and edits count too.
+const STREAM_EDIT_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Generates the text while editing the message with what is generated so far.
+///
+/// The message is left with the last partial text, the caller shows the final one.
+pub async fn generate_into_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    generator: &dyn ContentGenerator,
+    request: &GenerationRequest,
+    previous: Option<&Model>,
+) -> Result<Generation, ApiError> {
+    let (progress, receiver) = watch::channel(String::new());
+    let (stop, stopped) = oneshot::channel();
+
+    let follower = tokio::spawn(follow_progress(
+        bot.clone(),
+        chat_id,
+        message_id,
+        receiver,
+        stopped,
+    ));
+
+    let result = generator
+        .generate_streaming(request, previous, &progress)
+        .await;
+
+    // Waiting for the follower, so a late partial edit can't overwrite the final text
+    let _ = stop.send(());
+    if let Err(e) = follower.await {
+        warn!(error = %e, "Progress follower failed");
+    }
+
+    result
+}
+
+async fn follow_progress(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    mut progress: watch::Receiver<String>,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let mut shown = STREAM_PLACEHOLDER.to_string();
+    let mut delay = STREAM_EDIT_INTERVAL;
+
+    loop {
+        tokio::select! {
+            _ = &mut stop => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        delay = STREAM_EDIT_INTERVAL;
+
+        let progress = progress.borrow_and_update().clone();
+        let Some(text) = next_edit(&shown, &progress) else {
+            continue;
+        };
+        let text = text.to_string();
+
+        match bot
+            .edit_message_text(chat_id, message_id, text.clone())
+            .await
+        {
+            Ok(_) | Err(RequestError::Api(TelegramApiError::MessageNotModified)) => {
+                debug!(len = text.len(), "Generation progress shown");
+                shown = text;
+            }
+            Err(RequestError::RetryAfter(seconds)) => {
+                warn!(?seconds, "Progress edits are rate limited");
+                delay = seconds.duration();
+            }
+            Err(e) => warn!(error = %e, "Failed to show generation progress"),
+        }
+    }
+}
+
+/// Text the message should show now, `None` when it already does. Blank progress means
+/// the model failed and its partial text must not stay visible.
+fn next_edit<'a>(shown: &str, progress: &'a str) -> Option<&'a str> {
+    let text = if progress.trim().is_empty() {
+        STREAM_PLACEHOLDER
+    } else {
+        progress
+    };
+
+    (text != shown).then_some(text)
+}
+
+#[test]
+fn next_edit_test() {
+    assert_eq!(
+        next_edit(STREAM_PLACEHOLDER, "Нефорская"),
+        Some("Нефорская")
+    );
+    assert_eq!(
+        next_edit("Нефорская", "Нефорская пятница"),
+        Some("Нефорская пятница")
+    );
+    assert_eq!(next_edit("Нефорская", "Нефорская"), None);
+    assert_eq!(next_edit(STREAM_PLACEHOLDER, ""), None);
+    assert_eq!(next_edit(STREAM_PLACEHOLDER, "  "), None);
+
+    // A failed model's partial text is replaced with the placeholder again
+    assert_eq!(next_edit("Нефорская", ""), Some(STREAM_PLACEHOLDER));
+    assert_eq!(next_edit("Нефорская", "  "), Some(STREAM_PLACEHOLDER));
+}
@@ -0,0 +1,58 @@
+use crate::errors::ApiError;
+use crate::handlers::root_handler::SubscriptionStore;
+use crate::handlers::utils::get_user_id_from_option;
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::*;
+use teloxide::types::User;
+use tracing::{error, instrument};
+
+#[instrument(skip(bot, from, subscriptions))]
+pub async fn subscribe(
+    bot: Bot,
+    chat_id: ChatId,
+    from: Option<User>,
+    subscriptions: Arc<dyn SubscriptionStore>,
+) -> Result<(), ApiError> {
+    match subscriptions
+        .subscribe(chat_id, get_user_id_from_option(&from))
+        .await
+    {
+        Ok(true) => {
+            bot.send_message(chat_id, "Чат подписан на отсчет до нефорской пятницы 🕷️")
+                .await?;
+        }
+        Ok(false) => {
+            bot.send_message(chat_id, "Чат уже подписан").await?;
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to subscribe chat");
+            bot.send_message(chat_id, "Не удалось подписать чат")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(bot, subscriptions))]
+pub async fn unsubscribe(
+    bot: Bot,
+    chat_id: ChatId,
+    subscriptions: Arc<dyn SubscriptionStore>,
+) -> Result<(), ApiError> {
+    match subscriptions.unsubscribe(chat_id).await {
+        Ok(true) => {
+            bot.send_message(chat_id, "Чат отписан от отсчета").await?;
+        }
+        Ok(false) => {
+            bot.send_message(chat_id, "Чат не был подписан").await?;
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to unsubscribe chat");
+            bot.send_message(chat_id, "Не удалось отписать чат").await?;
+        }
+    }
+
+    Ok(())
+}
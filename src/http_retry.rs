@@ -0,0 +1,152 @@
+use crate::common::{Model, ensure_success};
+use crate::config::ProviderConfig;
+use crate::errors::ApiError;
+use crate::errors::ApiError::RequestError;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Retry rules shared by all HTTP based model providers.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl From<&ProviderConfig> for RetryPolicy {
+    fn from(provider: &ProviderConfig) -> Self {
+        RetryPolicy {
+            max_retries: provider.retries,
+            base_delay: Duration::from_millis(provider.backoff_base_ms),
+            max_delay: Duration::from_millis(provider.backoff_max_ms),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Full jitter: a random delay between zero and the exponential backoff cap.
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let cap = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+
+        cap.mul_f64(rand::random::<f64>())
+    }
+}
+
+/// Sends the request built by `build` until it succeeds or the policy gives up.
+/// Only network errors, 429 and 5xx are retried; `Retry-After` is honored unless it asks
+/// to wait longer than the policy allows, in which case the error is returned right away
+/// so the caller can move on to another model.
+pub async fn send_with_retry<F>(
+    model: &Model,
+    policy: &RetryPolicy,
+    build: F,
+) -> Result<Response, ApiError>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut attempt = 0;
+
+    loop {
+        let delay = match build().send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                let retry_after = parse_retry_after(response.headers());
+                let err = match ensure_success(model, response).await {
+                    Ok(response) => return Ok(response),
+                    Err(e) => e,
+                };
+
+                if !is_retryable_status(status) || attempt >= policy.max_retries {
+                    return Err(err);
+                }
+
+                match retry_after {
+                    Some(delay) if delay > policy.max_delay => {
+                        warn!(%model, ?delay, "Retry-After is too long, giving up on model");
+                        return Err(err);
+                    }
+                    Some(delay) => delay,
+                    None => policy.backoff_delay(attempt),
+                }
+            }
+            Err(e) => {
+                if !is_retryable_error(&e) || attempt >= policy.max_retries {
+                    return Err(RequestError(e));
+                }
+
+                warn!(%model, error = %e, "Request failed");
+                policy.backoff_delay(attempt)
+            }
+        };
+
+        attempt += 1;
+        debug!(%model, attempt, ?delay, "Retrying request");
+        tokio::time::sleep(delay).await;
+    }
+}
+
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request()
+}
+
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+#[test]
+fn retryable_status_test() {
+    assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+    assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+    assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+}
+
+#[test]
+fn parse_retry_after_test() {
+    use reqwest::header::HeaderValue;
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(parse_retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+    assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+
+    headers.insert(
+        RETRY_AFTER,
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+    assert_eq!(parse_retry_after(&headers), None);
+}
+
+#[test]
+fn backoff_delay_test() {
+    let policy = RetryPolicy {
+        max_retries: 5,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(1000),
+    };
+
+    for attempt in 0..10 {
+        let delay = policy.backoff_delay(attempt);
+        let cap = Duration::from_millis(100 * 2u64.pow(attempt)).min(policy.max_delay);
+        assert!(delay <= cap);
+    }
+}
@@ -4,36 +4,61 @@ mod common;
 mod config;
 mod constants;
 mod errors;
+mod formatting;
 mod generation_controller;
 mod gigachat_api;
-mod grok_api;
 mod handlers;
-mod mistral_api;
+mod http_retry;
+mod offline_api;
+mod ollama_api;
+mod openai_api;
+mod providers;
 mod repo;
+mod scheduler;
 mod states;
+mod streaming;
+#[cfg(test)]
+mod test_server;
 mod utils;
 
 use crate::adapter::postgres::PgStore;
-use crate::commands::Command;
+use crate::commands::{AdminCommand, Command};
 use crate::config::BotConfig;
-use crate::generation_controller::{ContentRephraser, GenerationController, ModelPool};
-use crate::grok_api::api::GrokApi;
+use crate::generation_controller::GenerationController;
+use crate::handlers::admin::handle_admin_command;
+use crate::handlers::conversation::{
+    ReplyLimiter, conversation_reply, is_conversation, remember_message,
+};
+use crate::handlers::generation_buttons::{GenerationAction, generation_callback};
 use crate::handlers::root_handler::{
-    handle_command, ContentGenerator, DialogueStore, MediaStore, MessageStore,
+    ChatHistoryStore, ChatSettingsStore, ContentGenerator, DialogueStore, MediaStore, MessageStore,
+    ModelStatsStore, PinnedCountdownStore, PromptTemplateStore, SubscriptionStore, VoteStore,
+    handle_command,
 };
 use crate::handlers::slay::inline_choice_callback;
 use crate::handlers::state_dispatcher::state_dispatcher;
-use crate::mistral_api::api::MistralApi;
+use crate::providers::build_model_pool;
+use crate::repo::chat_history_storage::ChatHistoryStorage;
+use crate::repo::chat_settings_storage_postgres::storage::PGChatSettingsStorage;
 use crate::repo::dialogue_storage::UserDialogueStorage;
+use crate::repo::generation_history_storage_postgres::storage::PGGenerationHistoryStorage;
 use crate::repo::media_storage_postgres::storage::PGMediaStorage;
-use crate::repo::message_history_storage::MessageHistoryStorage;
+use crate::repo::model_stats_storage_postgres::storage::PGModelStatsStorage;
+use crate::repo::pinned_countdown_storage_postgres::PGPinnedCountdownStorage;
+use crate::repo::prompt_template_storage_postgres::storage::PGPromptTemplateStorage;
+use crate::repo::subscription_storage_postgres::PGSubscriptionStorage;
+use crate::repo::vote_storage_postgres::storage::PGVoteStorage;
+use crate::scheduler::broadcast::FridayBroadcaster;
+use crate::scheduler::pinned_countdown::PinnedCountdownUpdater;
+use crate::scheduler::pregeneration::FridayPregenerator;
+use crate::scheduler::retention::HistoryRetention;
 use std::process;
 use std::sync::Arc;
 use teloxide::dispatching::UpdateFilterExt;
 use teloxide::prelude::*;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{EnvFilter, fmt};
 use url::Url;
 
 #[tokio::main]
@@ -58,20 +83,8 @@ async fn main() {
 
     let bot = Bot::new(cfg.tg_token);
 
-    // let gigachat_generator =
-    //     match GigaChatApi::new(cfg.gigachat_client_id, cfg.gigachat_client_secret) {
-    //         Ok(generator) => Arc::new(generator) as Arc<dyn ContentRephraser>,
-    //         Err(e) => {
-    //             eprintln!("error happened configuring generator: {}", e);
-    //             process::exit(1);
-    //         }
-    //     };
-
-    let mistral_generator =
-        Arc::new(MistralApi::new(cfg.mistral_token)) as Arc<dyn ContentRephraser>;
-
-    let grok_generator = match GrokApi::new(cfg.grok_token) {
-        Ok(generator) => Arc::new(generator) as Arc<dyn ContentRephraser>,
+    let model_pool = match build_model_pool(&cfg.models) {
+        Ok(pool) => pool,
         Err(e) => {
             eprintln!("error happened configuring generator: {}", e);
             process::exit(1);
@@ -86,14 +99,37 @@ async fn main() {
         }
     };
 
-    let media_storage = Arc::new(PGMediaStorage::new(pg_pool)) as Arc<dyn MediaStore>;
+    let media_storage = Arc::new(PGMediaStorage::new(pg_pool.clone())) as Arc<dyn MediaStore>;
+
+    let subscription_storage =
+        Arc::new(PGSubscriptionStorage::new(pg_pool.clone())) as Arc<dyn SubscriptionStore>;
+
+    let chat_settings_storage =
+        Arc::new(PGChatSettingsStorage::new(pg_pool.clone())) as Arc<dyn ChatSettingsStore>;
+
+    let pinned_countdown_storage =
+        Arc::new(PGPinnedCountdownStorage::new(pg_pool.clone())) as Arc<dyn PinnedCountdownStore>;
+
+    let prompt_template_storage =
+        Arc::new(PGPromptTemplateStorage::new(pg_pool.clone())) as Arc<dyn PromptTemplateStore>;
+
+    let vote_storage = Arc::new(PGVoteStorage::new(pg_pool.clone())) as Arc<dyn VoteStore>;
+
+    let model_stats_storage =
+        Arc::new(PGModelStatsStorage::new(pg_pool.clone())) as Arc<dyn ModelStatsStore>;
+
+    let message_history_storage =
+        Arc::new(PGGenerationHistoryStorage::new(pg_pool)) as Arc<dyn MessageStore>;
 
-    let message_history_storage = Arc::new(MessageHistoryStorage::new()) as Arc<dyn MessageStore>;
+    let pregenerate = cfg.models.generation.cache.pregenerate.clone();
+    let generation_controller = GenerationController::new(model_pool, cfg.models.generation)
+        .with_stats_store(model_stats_storage.clone());
 
-    let model_pool = ModelPool::from(vec![mistral_generator, grok_generator]);
+    if let Err(e) = generation_controller.restore_budgets().await {
+        eprintln!("error happened restoring model budgets: {}", e);
+    }
 
-    let generation_controller =
-        Arc::new(GenerationController::new(model_pool)) as Arc<dyn ContentGenerator>;
+    let generation_controller = Arc::new(generation_controller) as Arc<dyn ContentGenerator>;
 
     let (loki_layer, task) = match tracing_loki::builder()
         .label("service_name", "slay-friday-bot")
@@ -115,16 +151,82 @@ async fn main() {
     subscriber.init();
     tokio::spawn(task);
 
+    let broadcaster = FridayBroadcaster::new(
+        bot.clone(),
+        generation_controller.clone(),
+        message_history_storage.clone(),
+        subscription_storage.clone(),
+        chat_settings_storage.clone(),
+        prompt_template_storage.clone(),
+        cfg.broadcast_schedule,
+    );
+    tokio::spawn(broadcaster.run());
+
+    let pinned_countdown_updater = PinnedCountdownUpdater::new(
+        bot.clone(),
+        generation_controller.clone(),
+        message_history_storage.clone(),
+        chat_settings_storage.clone(),
+        pinned_countdown_storage.clone(),
+        prompt_template_storage.clone(),
+    );
+    tokio::spawn(pinned_countdown_updater.run());
+
+    if pregenerate.enabled {
+        let pregenerator = FridayPregenerator::new(
+            bot.clone(),
+            generation_controller.clone(),
+            subscription_storage.clone(),
+            chat_settings_storage.clone(),
+            prompt_template_storage.clone(),
+            &pregenerate,
+        );
+        tokio::spawn(pregenerator.run());
+    }
+
+    let history_retention = HistoryRetention::new(
+        message_history_storage.clone(),
+        model_stats_storage,
+        cfg.history_retention_days,
+    );
+    tokio::spawn(history_retention.run());
+
     let command_handler = dptree::entry()
         .filter_command::<Command>()
         .endpoint(handle_command);
 
+    let admin_command_handler = dptree::entry()
+        .filter_command::<AdminCommand>()
+        .endpoint(handle_admin_command);
+
     let dialogue_store = Arc::new(UserDialogueStorage::new()) as Arc<dyn DialogueStore>;
 
-    let callback_handler = Update::filter_callback_query().endpoint(inline_choice_callback);
+    let admin_ids = Arc::new(cfg.admin_ids);
+
+    let chat_history_storage = Arc::new(ChatHistoryStorage::new(
+        cfg.models.conversation.context_messages,
+    )) as Arc<dyn ChatHistoryStore>;
+
+    let reply_limiter = Arc::new(ReplyLimiter::new(&cfg.models.conversation));
+
+    let generation_callback_handler = dptree::filter_map(|q: CallbackQuery| {
+        q.data
+            .as_deref()
+            .and_then(|data| data.parse::<GenerationAction>().ok())
+    })
+    .endpoint(generation_callback);
+
+    let callback_handler = Update::filter_callback_query()
+        .branch(generation_callback_handler)
+        .endpoint(inline_choice_callback);
+
+    let conversation_handler = dptree::filter(is_conversation).endpoint(conversation_reply);
 
     let message_handler = Update::filter_message()
+        .inspect(remember_message)
         .branch(command_handler)
+        .branch(admin_command_handler)
+        .branch(conversation_handler)
         .endpoint(state_dispatcher);
 
     let handler = dptree::entry()
@@ -136,7 +238,15 @@ async fn main() {
             generation_controller,
             media_storage,
             message_history_storage,
-            dialogue_store
+            dialogue_store,
+            subscription_storage,
+            chat_settings_storage,
+            pinned_countdown_storage,
+            prompt_template_storage,
+            vote_storage,
+            admin_ids,
+            chat_history_storage,
+            reply_limiter
         ])
         .enable_ctrlc_handler()
         .default_handler(|_upd| async {})
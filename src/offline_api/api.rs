@@ -0,0 +1,114 @@
+use crate::common::Model;
+use crate::errors::ApiError;
+use crate::generation_controller::{Completion, ContentRephraser, GenerationRequest};
+use crate::offline_api::phrases::{ADDRESSES, COUNTDOWNS, EMOJI, OPENERS, SLANG};
+use async_trait::async_trait;
+use tracing::{info, instrument};
+
+/// Composes messages from built-in phrase banks, so it works without network and API keys.
+/// Meant to be the last resort of the pool and a stand-in for real models in development.
+#[derive(Debug)]
+pub struct OfflineRephraser {
+    model: Model,
+}
+
+impl OfflineRephraser {
+    pub fn new(name: &str) -> Self {
+        OfflineRephraser {
+            model: Model::new(name),
+        }
+    }
+}
+
+#[async_trait]
+impl ContentRephraser for OfflineRephraser {
+    #[instrument(skip(self, request), fields(model = %self.model))]
+    async fn rephrase_text(&self, request: &GenerationRequest) -> Result<Completion, ApiError> {
+        let text = compose(&request.text, |len| rand::random_range(0..len));
+        info!("Text composed offline");
+
+        Ok(Completion::from(text))
+    }
+
+    fn get_model_name(&self) -> Model {
+        self.model.clone()
+    }
+}
+
+/// Builds a new message around the countdown found in the input. Inputs without
+/// a countdown are kept as is and only decorated, so their meaning never changes.
+fn compose(input: &str, mut pick: impl FnMut(usize) -> usize) -> String {
+    let mut choose = |bank: &[&'static str]| bank[pick(bank.len()) % bank.len()];
+
+    let opener = choose(OPENERS);
+    let address = choose(ADDRESSES);
+
+    let body = match find_countdown(input) {
+        Some(countdown) => {
+            let sentence = choose(COUNTDOWNS).replace("{}", countdown);
+            format!("{} {}", sentence, choose(SLANG))
+        }
+        None => input.trim().to_string(),
+    };
+
+    format!("{}, {}! {} {}", opener, address, body, choose(EMOJI))
+}
+
+/// Returns the span from the first number to the unit word after the last one,
+/// e.g. "1 день, 2 часа, 1 минута".
+fn find_countdown(input: &str) -> Option<&str> {
+    let start = input.find(|c: char| c.is_ascii_digit())?;
+    let last_digit = input.rfind(|c: char| c.is_ascii_digit())?;
+
+    let after_number = &input[last_digit + 1..];
+    let unit_start = after_number.len() - after_number.trim_start().len();
+    let unit_len = after_number[unit_start..]
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(after_number.len() - unit_start);
+
+    Some(&input[start..last_digit + 1 + unit_start + unit_len])
+}
+
+#[test]
+fn find_countdown_test() {
+    assert_eq!(
+        find_countdown(
+            "До нефорской пятницы осталось: 1 день, 2 часа, 1 минута 🕷️ Готовь свой лучший аутфит. ⛓️"
+        ),
+        Some("1 день, 2 часа, 1 минута")
+    );
+    assert_eq!(
+        find_countdown("До нефорской пятницы осталось: 45 секунд"),
+        Some("45 секунд")
+    );
+    assert_eq!(find_countdown("ЭТО НЕФОРСКАЯ ПЯТНИЦА, ДЕТКА!"), None);
+}
+
+#[test]
+fn offline_compose_test() {
+    use crate::generation_controller::validation::{OutputValidator, ValidationConfig};
+
+    let input = "До нефорской пятницы осталось: 3 дня, 4 часа 🕷️ Готовь свой лучший аутфит. ⛓️";
+    let validator = OutputValidator::new(ValidationConfig::default());
+
+    assert_eq!(
+        compose(input, |_| 1),
+        "Слушайте сюда, чертята! Нефорская пятница наступит через 3 дня, 4 часа. Доставай кожанку из шкафа. ⛓️"
+    );
+
+    // Every combination of phrases must pass the validation of real model answers
+    for seed in 0..64 {
+        let mut state = seed;
+        let text = compose(input, |len| {
+            state = state * 31 + 7;
+            state % len
+        });
+        assert!(validator.validate(input, &text).is_ok(), "{}", text);
+    }
+
+    let started = "SLAAAAAY! ЭТО НЕФОРСКАЯ ПЯТНИЦА, ДЕТКА!";
+    assert_eq!(
+        compose(started, |_| 0),
+        "Внимание, котаны! SLAAAAAY! ЭТО НЕФОРСКАЯ ПЯТНИЦА, ДЕТКА! 🖤"
+    );
+}
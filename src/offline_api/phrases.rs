@@ -0,0 +1,42 @@
+//! Phrase banks the offline generator composes its messages from.
+
+pub const OPENERS: &[&str] = &[
+    "Внимание",
+    "Слушайте сюда",
+    "Срочные новости",
+    "Так-так-так",
+    "Эй",
+    "Шухер",
+    "Алло",
+];
+
+pub const ADDRESSES: &[&str] = &[
+    "котаны",
+    "чертята",
+    "нефоры",
+    "готы",
+    "детки",
+    "панки",
+    "летучие мыши",
+];
+
+/// `{}` is replaced with the exact countdown.
+pub const COUNTDOWNS: &[&str] = &[
+    "До нефорской пятницы осталось {}.",
+    "Нефорская пятница наступит через {}.",
+    "Всего {} — и начнется нефорская пятница.",
+    "Через {} стартует нефорская пятница.",
+    "Нефорской пятнице осталось ждать {}.",
+];
+
+pub const SLANG: &[&str] = &[
+    "Готовь цепи и подводку.",
+    "Доставай кожанку из шкафа.",
+    "Пора начищать гриндерсы.",
+    "Вайб уже на подходе.",
+    "Кринж оставь до понедельника.",
+    "Без черного лака не приходи.",
+    "Будет жестко, будет красиво.",
+];
+
+pub const EMOJI: &[&str] = &["🖤", "⛓️", "🦇", "🕷️", "🤘", "💀", "🔥", "😈"];
@@ -0,0 +1,315 @@
+use crate::common::Model;
+use crate::config::{OllamaProviderConfig, ProviderConfig};
+use crate::errors::ApiError;
+use crate::errors::ApiError::{
+    ApiClientBuildError, DecodeResponseError, ModelError, NoContent, TransformJSONError,
+};
+use crate::generation_controller::{Completion, ContentRephraser, GenerationRequest, TextProgress};
+use crate::http_retry::{RetryPolicy, send_with_retry};
+use crate::ollama_api::dto::{OllamaChatRequest, OllamaChatResponse, OllamaMessage};
+use crate::streaming::read_lines;
+use async_trait::async_trait;
+use reqwest::{Client, Response, Url};
+use serde_json::{Map, Value};
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, warn};
+
+/// Client for a self-hosted model behind the Ollama `/api/chat` endpoint.
+#[derive(Debug)]
+pub struct OllamaApi {
+    client: Client,
+    url: Url,
+    model_name: String,
+    stream: bool,
+    options: Map<String, Value>,
+    retry_policy: RetryPolicy,
+    model: Model,
+}
+
+impl OllamaApi {
+    pub fn new(provider: &ProviderConfig, config: &OllamaProviderConfig) -> Result<Self, ApiError> {
+        let url = Url::parse(&format!("{}/api/chat", config.host.trim_end_matches('/')))?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(provider.timeout_secs))
+            .build()
+            .map_err(ApiClientBuildError)?;
+
+        Ok(OllamaApi {
+            client,
+            url,
+            model_name: config.model.clone(),
+            stream: config.stream,
+            options: config.options.clone(),
+            retry_policy: RetryPolicy::from(provider),
+            model: Model::new(provider.name.as_str()),
+        })
+    }
+
+    async fn read_whole(&self, response: Response) -> Result<Completion, ApiError> {
+        let response: OllamaChatResponse = response.json().await.map_err(DecodeResponseError)?;
+
+        if let Some(error) = response.error {
+            return Err(ModelError(self.model.clone(), error));
+        }
+
+        let usage = response.usage();
+        let text = response.message.map(|m| m.content).unwrap_or_default();
+
+        Ok(Completion::new(text, usage))
+    }
+
+    /// Collects the NDJSON stream, publishing the text to `progress` when there is one.
+    async fn read_stream(
+        &self,
+        response: Response,
+        progress: Option<&TextProgress>,
+    ) -> Result<Completion, ApiError> {
+        let mut text = String::new();
+        let mut usage = None;
+
+        let finished = read_lines(response, |line| {
+            let part: OllamaChatResponse =
+                serde_json::from_str(line).map_err(TransformJSONError)?;
+
+            if let Some(error) = part.error {
+                return Err(ModelError(self.model.clone(), error));
+            }
+
+            if let Some(message) = &part.message {
+                debug!(len = message.content.len(), "Received stream part");
+                text.push_str(&message.content);
+                if let Some(progress) = progress {
+                    progress.send_modify(|shown| shown.push_str(&message.content));
+                }
+            }
+
+            if part.done {
+                usage = part.usage();
+            }
+
+            Ok(part.done)
+        })
+        .await?;
+
+        if !finished {
+            warn!("Ollama stream ended without done message");
+        }
+
+        Ok(Completion::new(text, usage))
+    }
+
+    async fn generate(
+        &self,
+        request: &GenerationRequest,
+        progress: Option<&TextProgress>,
+    ) -> Result<Completion, ApiError> {
+        let stream = self.stream || progress.is_some();
+        let body = OllamaChatRequest {
+            model: &self.model_name,
+            messages: vec![
+                OllamaMessage::new("system", request.system_prompt.clone()),
+                OllamaMessage::new("user", request.text.clone()),
+            ],
+            stream,
+            options: &self.options,
+        };
+
+        let response = send_with_retry(&self.model, &self.retry_policy, || {
+            self.client.post(self.url.clone()).json(&body)
+        })
+        .await
+        .inspect_err(|e| error!(error = %e, "Failed to generate content"))?;
+
+        let completion = if stream {
+            self.read_stream(response, progress).await?
+        } else {
+            self.read_whole(response).await?
+        };
+
+        if completion.text.trim().is_empty() {
+            warn!("Model returned 200 OK but empty message");
+            return Err(NoContent);
+        }
+
+        info!(usage = ?completion.usage, "Text rephrased successfully");
+        Ok(completion)
+    }
+}
+
+#[async_trait]
+impl ContentRephraser for OllamaApi {
+    #[instrument(skip(self, request), fields(model = %self.model, stream = self.stream), err)]
+    async fn rephrase_text(&self, request: &GenerationRequest) -> Result<Completion, ApiError> {
+        info!("Starting generation");
+        self.generate(request, None).await
+    }
+
+    #[instrument(skip(self, request, progress), fields(model = %self.model), err)]
+    async fn rephrase_text_streaming(
+        &self,
+        request: &GenerationRequest,
+        progress: &TextProgress,
+    ) -> Result<Completion, ApiError> {
+        info!("Starting streaming generation");
+        self.generate(request, Some(progress)).await
+    }
+
+    fn get_model_name(&self) -> Model {
+        self.model.clone()
+    }
+}
+
+#[cfg(test)]
+fn fake_api(server: &crate::test_server::FakeServer, stream: bool) -> OllamaApi {
+    use crate::config::ProviderKind;
+
+    let raw = format!(
+        r#"
+        name = "Local"
+        kind = "ollama"
+        model = "qwen2.5:7b"
+        host = "{}"
+        stream = {}
+        options = {{ temperature = 0.8 }}
+        retries = 0
+        "#,
+        server.url(),
+        stream
+    );
+
+    let provider: ProviderConfig = toml::from_str(&raw).unwrap();
+    let ProviderKind::Ollama(config) = &provider.kind else {
+        unreachable!("provider kind is ollama");
+    };
+
+    OllamaApi::new(&provider, config).unwrap()
+}
+
+#[cfg(test)]
+const CHAT_PATH: &str = "/api/chat";
+
+#[tokio::test]
+async fn ollama_api_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(
+        CHAT_PATH,
+        [FakeResponse::json(
+            200,
+            r#"{"model": "qwen2.5:7b", "message": {"role": "assistant", "content": "Нефорская пятница"},
+                "done": true, "prompt_eval_count": 30, "eval_count": 9}"#,
+        )],
+    );
+
+    let completion = fake_api(&server, false)
+        .rephrase_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert_eq!(
+        completion.usage,
+        Some(crate::generation_controller::TokenUsage {
+            prompt_tokens: 30,
+            completion_tokens: 9,
+        })
+    );
+
+    let body: Value = serde_json::from_str(&server.requests(CHAT_PATH)[0].body).unwrap();
+    assert_eq!(body["model"], "qwen2.5:7b");
+    assert_eq!(body["stream"], false);
+    assert_eq!(body["options"]["temperature"], 0.8);
+    assert_eq!(body["messages"][0]["role"], "system");
+}
+
+#[tokio::test]
+async fn ollama_api_stream_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    let api = fake_api(&server, true);
+
+    server.respond(
+        CHAT_PATH,
+        [FakeResponse::json(
+            200,
+            concat!(
+                r#"{"message": {"role": "assistant", "content": "Нефорская "}, "done": false}"#,
+                "\n",
+                r#"{"message": {"role": "assistant", "content": "пятница"}, "done": false}"#,
+                "\n",
+                r#"{"message": {"role": "assistant", "content": ""}, "done": true, "prompt_eval_count": 30, "eval_count": 2}"#,
+                "\n"
+            ),
+        )],
+    );
+
+    let completion = api
+        .rephrase_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert_eq!(completion.usage.map(|u| u.completion_tokens), Some(2));
+
+    let body: Value = serde_json::from_str(&server.requests(CHAT_PATH)[0].body).unwrap();
+    assert_eq!(body["stream"], true);
+
+    let (progress, shown) = tokio::sync::watch::channel(String::new());
+    let completion = fake_api(&server, false)
+        .rephrase_text_streaming(&test_request("some test text"), &progress)
+        .await
+        .unwrap();
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert_eq!(*shown.borrow(), "Нефорская пятница");
+
+    server.respond(
+        CHAT_PATH,
+        [FakeResponse::json(
+            200,
+            concat!(
+                r#"{"message": {"role": "assistant", "content": "Нефорская "}, "done": false}"#,
+                "\n",
+                r#"{"error": "model runner has unexpectedly stopped"}"#
+            ),
+        )],
+    );
+
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(ModelError(_, _))));
+}
+
+#[tokio::test]
+async fn ollama_api_errors_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    let api = fake_api(&server, false);
+
+    server.respond(
+        CHAT_PATH,
+        [FakeResponse::json(
+            404,
+            r#"{"error": "model 'qwen2.5:7b' not found"}"#,
+        )],
+    );
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(ApiError::ApiStatusError { .. })));
+
+    server.respond(
+        CHAT_PATH,
+        [FakeResponse::json(
+            200,
+            r#"{"message": {"role": "assistant", "content": " "}, "done": true}"#,
+        )],
+    );
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(NoContent)));
+
+    server.respond(CHAT_PATH, [FakeResponse::json(200, "<html>")]);
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(DecodeResponseError(_))));
+}
@@ -0,0 +1,52 @@
+use crate::generation_controller::TokenUsage;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Debug)]
+pub struct OllamaChatRequest<'a> {
+    pub model: &'a str,
+    pub messages: Vec<OllamaMessage>,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub options: &'a Map<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OllamaMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl OllamaMessage {
+    pub fn new(role: &str, content: String) -> Self {
+        OllamaMessage {
+            role: role.to_string(),
+            content,
+        }
+    }
+}
+
+/// Whole answer in non-streaming mode, one line of the NDJSON body in streaming mode.
+#[derive(Deserialize, Debug)]
+pub struct OllamaChatResponse {
+    #[serde(default)]
+    pub message: Option<OllamaMessage>,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    pub eval_count: Option<u32>,
+    /// Set instead of the message when the model fails in the middle of a stream.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl OllamaChatResponse {
+    pub fn usage(&self) -> Option<TokenUsage> {
+        Some(TokenUsage {
+            prompt_tokens: self.prompt_eval_count?,
+            completion_tokens: self.eval_count?,
+        })
+    }
+}
@@ -0,0 +1,328 @@
+use crate::common::Model;
+use crate::config::{OpenAiProviderConfig, ProviderConfig};
+use crate::errors::ApiError;
+use crate::errors::ApiError::{
+    ApiClientBuildError, ApiKeyNotFound, DecodeResponseError, NoContent,
+};
+use crate::generation_controller::{Completion, ContentRephraser, GenerationRequest, TextProgress};
+use crate::http_retry::{RetryPolicy, send_with_retry};
+use crate::openai_api::dto::{
+    OpenAiGenerateTextRequest, OpenAiGenerateTextResponse, OpenAiMessage, OpenAiStreamChunk,
+    OpenAiStreamOptions,
+};
+use crate::streaming::{SSE_DONE, read_lines, sse_data};
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde_json::{Map, Value};
+use std::env;
+use std::time::Duration;
+use tracing::{error, info, instrument, warn};
+
+/// Client for any server speaking the OpenAI chat completions protocol
+/// (Mistral, xAI, DeepSeek, OpenRouter, llama.cpp, Ollama, ...).
+#[derive(Debug)]
+pub struct OpenAiCompatibleApi {
+    client: Client,
+    url: Url,
+    token: Option<String>,
+    model_name: String,
+    extra_params: Map<String, Value>,
+    retry_policy: RetryPolicy,
+    model: Model,
+}
+
+impl OpenAiCompatibleApi {
+    pub fn new(provider: &ProviderConfig, config: &OpenAiProviderConfig) -> Result<Self, ApiError> {
+        let url = Url::parse(&format!(
+            "{}/chat/completions",
+            config.base_url.trim_end_matches('/')
+        ))?;
+
+        let token = match &config.api_key_env {
+            Some(var) => Some(env::var(var).map_err(|e| ApiKeyNotFound(var.clone(), e))?),
+            None => None,
+        };
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(provider.timeout_secs))
+            .build()
+            .map_err(ApiClientBuildError)?;
+
+        Ok(OpenAiCompatibleApi {
+            client,
+            url,
+            token,
+            model_name: config.model.clone(),
+            extra_params: config.extra_params.clone(),
+            retry_policy: RetryPolicy::from(provider),
+            model: Model::new(provider.name.as_str()),
+        })
+    }
+}
+
+impl OpenAiCompatibleApi {
+    async fn send(
+        &self,
+        request: &GenerationRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response, ApiError> {
+        let body = OpenAiGenerateTextRequest {
+            model: &self.model_name,
+            messages: vec![
+                OpenAiMessage::new("system", request.system_prompt.clone()),
+                OpenAiMessage::new("user", request.text.clone()),
+            ],
+            stream,
+            stream_options: stream.then_some(OpenAiStreamOptions {
+                include_usage: true,
+            }),
+            extra_params: &self.extra_params,
+        };
+
+        send_with_retry(&self.model, &self.retry_policy, || {
+            let builder = self.client.post(self.url.clone()).json(&body);
+            match &self.token {
+                Some(token) => builder.bearer_auth(token),
+                None => builder,
+            }
+        })
+        .await
+        .inspect_err(|e| error!(error = %e, "Failed to generate content"))
+    }
+}
+
+#[async_trait]
+impl ContentRephraser for OpenAiCompatibleApi {
+    #[instrument(skip(self, request), fields(model = %self.model), err)]
+    async fn rephrase_text(&self, request: &GenerationRequest) -> Result<Completion, ApiError> {
+        info!("Starting generation");
+
+        let response = self.send(request, false).await?;
+
+        let response: OpenAiGenerateTextResponse =
+            response.json().await.map_err(DecodeResponseError)?;
+
+        if let Some(new_text) = response.choices.into_iter().next() {
+            info!(usage = ?response.usage, "Text rephrased successfully");
+            return Ok(Completion::new(new_text.message.content, response.usage));
+        }
+
+        warn!("Model returned 200 OK but empty choices");
+        Err(NoContent)
+    }
+
+    #[instrument(skip(self, request, progress), fields(model = %self.model), err)]
+    async fn rephrase_text_streaming(
+        &self,
+        request: &GenerationRequest,
+        progress: &TextProgress,
+    ) -> Result<Completion, ApiError> {
+        info!("Starting streaming generation");
+
+        let response = self.send(request, true).await?;
+
+        let mut text = String::new();
+        let mut usage = None;
+
+        let finished = read_lines(response, |line| {
+            let Some(data) = sse_data(line) else {
+                return Ok(false);
+            };
+            if data == SSE_DONE {
+                return Ok(true);
+            }
+
+            let chunk: OpenAiStreamChunk = serde_json::from_str(data)?;
+            usage = chunk.usage.or(usage);
+
+            if let Some(delta) = chunk.choices.into_iter().find_map(|c| c.delta.content) {
+                text.push_str(&delta);
+                progress.send_modify(|shown| shown.push_str(&delta));
+            }
+
+            Ok(false)
+        })
+        .await?;
+
+        if !finished {
+            warn!("Stream ended without done marker");
+        }
+
+        if text.trim().is_empty() {
+            warn!("Model streamed no content");
+            return Err(NoContent);
+        }
+
+        info!(?usage, "Text streamed successfully");
+        Ok(Completion::new(text, usage))
+    }
+
+    fn get_model_name(&self) -> Model {
+        self.model.clone()
+    }
+}
+
+#[cfg(test)]
+fn fake_api(server: &crate::test_server::FakeServer) -> OpenAiCompatibleApi {
+    use crate::config::ProviderKind;
+
+    let raw = format!(
+        r#"
+        name = "Fake"
+        kind = "openai"
+        base_url = "{}/v1"
+        model = "fake-model"
+        retries = 1
+        backoff_base_ms = 1
+        backoff_max_ms = 5
+        "#,
+        server.url()
+    );
+
+    let provider: ProviderConfig = toml::from_str(&raw).unwrap();
+    let ProviderKind::OpenAi(config) = &provider.kind else {
+        unreachable!("provider kind is openai");
+    };
+
+    OpenAiCompatibleApi::new(&provider, config).unwrap()
+}
+
+#[cfg(test)]
+const COMPLETIONS_PATH: &str = "/v1/chat/completions";
+
+#[tokio::test]
+async fn openai_api_success_test() {
+    use crate::generation_controller::{TokenUsage, test_request};
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::completion("Нефорская пятница")],
+    );
+
+    let completion = fake_api(&server)
+        .rephrase_text(&test_request("some test text"))
+        .await
+        .unwrap();
+
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert_eq!(
+        completion.usage,
+        Some(TokenUsage {
+            prompt_tokens: 12,
+            completion_tokens: 7,
+        })
+    );
+
+    let requests = server.requests(COMPLETIONS_PATH);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+
+    let body: Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["model"], "fake-model");
+    assert_eq!(body["messages"][0]["content"], "test prompt");
+    assert_eq!(body["messages"][1]["content"], "some test text");
+    assert!(body.get("stream_options").is_none());
+}
+
+#[tokio::test]
+async fn openai_api_streaming_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::stream(&["Нефорская", " пятница"])],
+    );
+
+    let (progress, shown) = tokio::sync::watch::channel(String::new());
+    let completion = fake_api(&server)
+        .rephrase_text_streaming(&test_request("some test text"), &progress)
+        .await
+        .unwrap();
+
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert_eq!(completion.usage.map(|u| u.completion_tokens), Some(7));
+    assert_eq!(*shown.borrow(), "Нефорская пятница");
+
+    let body: Value = serde_json::from_str(&server.requests(COMPLETIONS_PATH)[0].body).unwrap();
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["stream_options"]["include_usage"], true);
+
+    server.respond(COMPLETIONS_PATH, [FakeResponse::stream(&[])]);
+    let result = fake_api(&server)
+        .rephrase_text_streaming(&test_request("some test text"), &progress)
+        .await;
+    assert!(matches!(result, Err(ApiError::NoContent)));
+}
+
+#[tokio::test]
+async fn openai_api_retryable_status_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    server.respond(
+        COMPLETIONS_PATH,
+        [
+            FakeResponse::json(429, r#"{"error": "rate limited"}"#).with_header("Retry-After", "0"),
+            FakeResponse::completion("Нефорская пятница"),
+        ],
+    );
+
+    let completion = fake_api(&server)
+        .rephrase_text(&test_request("some test text"))
+        .await
+        .unwrap();
+    assert_eq!(completion.text, "Нефорская пятница");
+    assert_eq!(server.requests(COMPLETIONS_PATH).len(), 2);
+
+    let server = FakeServer::start().await;
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::json(500, r#"{"error": "boom"}"#)],
+    );
+
+    let result = fake_api(&server)
+        .rephrase_text(&test_request("some test text"))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ApiError::ApiStatusError { status, .. }) if status == reqwest::StatusCode::INTERNAL_SERVER_ERROR
+    ));
+    assert_eq!(server.requests(COMPLETIONS_PATH).len(), 2);
+}
+
+#[tokio::test]
+async fn openai_api_bad_response_test() {
+    use crate::generation_controller::test_request;
+    use crate::test_server::{FakeResponse, FakeServer};
+
+    let server = FakeServer::start().await;
+    let api = fake_api(&server);
+
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::json(200, r#"{"choices": []}"#)],
+    );
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(NoContent)));
+
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::json(200, r#"{"choices": ["#)],
+    );
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(DecodeResponseError(_))));
+
+    // Client errors other than 429 are not retried
+    server.respond(
+        COMPLETIONS_PATH,
+        [FakeResponse::json(400, r#"{"error": "bad"}"#)],
+    );
+    let result = api.rephrase_text(&test_request("some test text")).await;
+    assert!(matches!(result, Err(ApiError::ApiStatusError { .. })));
+    assert_eq!(server.requests(COMPLETIONS_PATH).len(), 3);
+}
@@ -0,0 +1,91 @@
+use crate::generation_controller::TokenUsage;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Debug)]
+pub struct OpenAiGenerateTextRequest<'a> {
+    pub model: &'a str,
+    pub messages: Vec<OpenAiMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+    /// Without it strict backends leave `usage` out of streamed answers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<OpenAiStreamOptions>,
+    #[serde(flatten)]
+    pub extra_params: &'a Map<String, Value>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OpenAiStreamOptions {
+    pub include_usage: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OpenAiMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl OpenAiMessage {
+    pub fn new(role: &str, content: String) -> Self {
+        OpenAiMessage {
+            role: role.to_string(),
+            content,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OpenAiGenerateTextResponse {
+    pub choices: Vec<OpenAiChoiceResponse>,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OpenAiChoiceResponse {
+    pub message: OpenAiMessage,
+}
+
+/// One server-sent event of a streamed answer, usage comes with the last one.
+#[derive(Deserialize, Debug)]
+pub struct OpenAiStreamChunk {
+    #[serde(default)]
+    pub choices: Vec<OpenAiStreamChoice>,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OpenAiStreamChoice {
+    #[serde(default)]
+    pub delta: OpenAiDelta,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct OpenAiDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+#[test]
+fn openai_response_usage_test() {
+    let raw = r#"{
+        "id": "cmpl-1",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Нефорская пятница"}}],
+        "usage": {"prompt_tokens": 42, "completion_tokens": 17, "total_tokens": 59}
+    }"#;
+
+    let response: OpenAiGenerateTextResponse = serde_json::from_str(raw).unwrap();
+    assert_eq!(
+        response.usage,
+        Some(TokenUsage {
+            prompt_tokens: 42,
+            completion_tokens: 17,
+        })
+    );
+
+    let raw = r#"{"choices": []}"#;
+    let response: OpenAiGenerateTextResponse = serde_json::from_str(raw).unwrap();
+    assert_eq!(response.usage, None);
+}
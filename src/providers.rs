@@ -0,0 +1,34 @@
+use crate::config::{ModelsConfig, ProviderKind};
+use crate::errors::ApiError;
+use crate::generation_controller::{ContentRephraser, ModelPool, PooledModel};
+use crate::gigachat_api::api::GigaChatApi;
+use crate::offline_api::api::OfflineRephraser;
+use crate::ollama_api::api::OllamaApi;
+use crate::openai_api::api::OpenAiCompatibleApi;
+use std::sync::Arc;
+use tracing::info;
+
+/// Builds the pool from enabled providers only, so credentials of disabled ones are never required.
+pub fn build_model_pool(config: &ModelsConfig) -> Result<ModelPool, ApiError> {
+    let mut pool = ModelPool::new();
+
+    for provider in config.providers.iter().filter(|p| p.enabled) {
+        let rephraser: Arc<dyn ContentRephraser> = match &provider.kind {
+            ProviderKind::OpenAi(c) => Arc::new(OpenAiCompatibleApi::new(provider, c)?),
+            ProviderKind::GigaChat(c) => Arc::new(GigaChatApi::new(provider, c)?),
+            ProviderKind::Ollama(c) => Arc::new(OllamaApi::new(provider, c)?),
+            ProviderKind::Offline => Arc::new(OfflineRephraser::new(&provider.name)),
+        };
+
+        info!(provider = %provider.name, weight = provider.weight, "Model provider enabled");
+        let mut pooled = PooledModel::new(rephraser, provider.weight)
+            .with_billing(provider.pricing.clone(), provider.budget.clone());
+        if provider.last_resort {
+            pooled = pooled.with_last_resort();
+        }
+
+        pool.push(pooled);
+    }
+
+    Ok(pool)
+}
@@ -0,0 +1,83 @@
+use crate::handlers::root_handler::ChatHistoryStore;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use teloxide::types::{ChatId, MessageId};
+
+/// One message seen in a chat, kept as conversation context for replies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatLine {
+    pub message_id: MessageId,
+    pub author: String,
+    pub text: String,
+}
+
+impl ChatLine {
+    pub fn new(message_id: MessageId, author: impl Into<String>, text: impl Into<String>) -> Self {
+        ChatLine {
+            message_id,
+            author: author.into(),
+            text: text.into(),
+        }
+    }
+}
+
+/// Last messages of every chat in memory, the bot only sees what Telegram delivers to it.
+pub struct ChatHistoryStorage {
+    storage: DashMap<ChatId, VecDeque<ChatLine>>,
+    capacity: usize,
+}
+
+impl ChatHistoryStorage {
+    pub fn new(capacity: usize) -> Self {
+        ChatHistoryStorage {
+            storage: DashMap::new(),
+            capacity,
+        }
+    }
+}
+
+impl ChatHistoryStore for ChatHistoryStorage {
+    fn remember(&self, chat_id: ChatId, line: ChatLine) {
+        let mut lines = self.storage.entry(chat_id).or_default();
+        if lines.iter().any(|l| l.message_id == line.message_id) {
+            return;
+        }
+
+        lines.push_back(line);
+        while lines.len() > self.capacity {
+            lines.pop_front();
+        }
+    }
+
+    fn recent(&self, chat_id: ChatId) -> Vec<ChatLine> {
+        self.storage
+            .get(&chat_id)
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[test]
+fn chat_history_storage_test() {
+    let storage = ChatHistoryStorage::new(2);
+    let chat_id = ChatId(42);
+
+    storage.remember(
+        chat_id,
+        ChatLine::new(MessageId(1), "Ира", "Когда пятница?"),
+    );
+    storage.remember(
+        chat_id,
+        ChatLine::new(MessageId(1), "Ира", "Когда пятница?"),
+    );
+    storage.remember(chat_id, ChatLine::new(MessageId(2), "Бот", "Скоро"));
+    storage.remember(chat_id, ChatLine::new(MessageId(3), "Ира", "А точнее?"));
+
+    let ids: Vec<MessageId> = storage
+        .recent(chat_id)
+        .iter()
+        .map(|l| l.message_id)
+        .collect();
+    assert_eq!(ids, [MessageId(2), MessageId(3)]);
+    assert!(storage.recent(ChatId(7)).is_empty());
+}
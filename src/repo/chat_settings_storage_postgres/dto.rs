@@ -0,0 +1,71 @@
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
+use sqlx::FromRow;
+use teloxide::types::ChatId;
+use tracing::warn;
+
+pub const DEFAULT_TIMEZONE: Tz = chrono_tz::Europe::Moscow;
+pub const DEFAULT_WEEKDAY: Weekday = Weekday::Fri;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatSettings {
+    pub chat_id: ChatId,
+    pub timezone: Tz,
+    pub weekday: Weekday,
+    pub start_time: NaiveTime,
+    /// When `end_time` is not after `start_time` the event ends on the next day.
+    pub end_time: NaiveTime,
+    /// Name of the active prompt template, the built-in one when not set.
+    pub prompt_template: Option<String>,
+    /// Whether the bot answers when it is mentioned or replied to.
+    pub replies_enabled: bool,
+}
+
+impl ChatSettings {
+    pub fn new(chat_id: ChatId) -> Self {
+        ChatSettings {
+            chat_id,
+            timezone: DEFAULT_TIMEZONE,
+            weekday: DEFAULT_WEEKDAY,
+            start_time: NaiveTime::MIN,
+            end_time: NaiveTime::MIN,
+            prompt_template: None,
+            replies_enabled: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ChatSettingsRow {
+    pub chat_id: i64,
+    pub timezone: String,
+    pub weekday: i16,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub prompt_template: Option<String>,
+    pub replies_enabled: bool,
+}
+
+impl From<ChatSettingsRow> for ChatSettings {
+    fn from(row: ChatSettingsRow) -> Self {
+        let timezone = row.timezone.parse::<Tz>().unwrap_or_else(|_| {
+            warn!(chat_id = row.chat_id, timezone = %row.timezone, "Unknown timezone in settings");
+            DEFAULT_TIMEZONE
+        });
+
+        let weekday = u8::try_from(row.weekday - 1)
+            .ok()
+            .and_then(|d| Weekday::try_from(d).ok())
+            .unwrap_or(DEFAULT_WEEKDAY);
+
+        ChatSettings {
+            chat_id: ChatId(row.chat_id),
+            timezone,
+            weekday,
+            start_time: row.start_time,
+            end_time: row.end_time,
+            prompt_template: row.prompt_template,
+            replies_enabled: row.replies_enabled,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::ChatSettingsStore;
+use crate::repo::chat_settings_storage_postgres::dto::{ChatSettings, ChatSettingsRow};
+use async_trait::async_trait;
+use teloxide::types::ChatId;
+
+pub struct PGChatSettingsStorage {
+    storage: PgStore,
+}
+
+impl PGChatSettingsStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl ChatSettingsStore for PGChatSettingsStorage {
+    async fn get_settings(&self, chat_id: ChatId) -> Result<ChatSettings, ApiError> {
+        let row = sqlx::query_as::<_, ChatSettingsRow>(
+            r"select chat_id, timezone, weekday, start_time, end_time, prompt_template,
+                    replies_enabled
+                from chat_settings where chat_id = $1;",
+        )
+        .bind(chat_id.0)
+        .fetch_optional(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(row
+            .map(ChatSettings::from)
+            .unwrap_or_else(|| ChatSettings::new(chat_id)))
+    }
+
+    async fn save_settings(&self, settings: &ChatSettings) -> Result<(), ApiError> {
+        sqlx::query(
+            r"insert into chat_settings
+                (chat_id, timezone, weekday, start_time, end_time, prompt_template,
+                    replies_enabled)
+                values ($1, $2, $3, $4, $5, $6, $7)
+                on conflict (chat_id) do update
+                set timezone = excluded.timezone,
+                    weekday = excluded.weekday,
+                    start_time = excluded.start_time,
+                    end_time = excluded.end_time,
+                    prompt_template = excluded.prompt_template,
+                    replies_enabled = excluded.replies_enabled,
+                    updated_at = current_timestamp;",
+        )
+        .bind(settings.chat_id.0)
+        .bind(settings.timezone.name())
+        .bind(settings.weekday.number_from_monday() as i16)
+        .bind(settings.start_time)
+        .bind(settings.end_time)
+        .bind(&settings.prompt_template)
+        .bind(settings.replies_enabled)
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::common::Model;
+use crate::generation_controller::{Generation, GenerationRequest, TokenUsage};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::time::Duration;
+use teloxide::types::{ChatId, MessageId};
+use uuid::Uuid;
+
+/// One generated message sent to a chat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub id: Uuid,
+    pub chat_id: ChatId,
+    pub message_id: MessageId,
+    pub model: Model,
+    pub prompt_template: String,
+    pub input_text: String,
+    pub output_text: String,
+    pub latency: Duration,
+    pub usage: Option<TokenUsage>,
+    pub cost: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        chat_id: ChatId,
+        message_id: MessageId,
+        request: &GenerationRequest,
+        generation: &Generation,
+    ) -> Self {
+        HistoryEntry {
+            id: Uuid::new_v4(),
+            chat_id,
+            message_id,
+            model: generation.model.clone(),
+            prompt_template: request.template.clone(),
+            input_text: request.text.clone(),
+            output_text: generation.text.clone(),
+            latency: generation.latency,
+            usage: generation.usage,
+            cost: generation.cost,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct HistoryEntryRow {
+    pub id: Uuid,
+    pub chat_id: i64,
+    pub message_id: i32,
+    pub model: String,
+    pub prompt_template: String,
+    pub input_text: String,
+    pub output_text: String,
+    pub latency_ms: i32,
+    pub prompt_tokens: Option<i32>,
+    pub completion_tokens: Option<i32>,
+    pub cost: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<HistoryEntryRow> for HistoryEntry {
+    fn from(row: HistoryEntryRow) -> Self {
+        HistoryEntry {
+            id: row.id,
+            chat_id: ChatId(row.chat_id),
+            message_id: MessageId(row.message_id),
+            model: Model::new(row.model),
+            prompt_template: row.prompt_template,
+            input_text: row.input_text,
+            output_text: row.output_text,
+            latency: Duration::from_millis(row.latency_ms.max(0) as u64),
+            usage: row
+                .prompt_tokens
+                .zip(row.completion_tokens)
+                .map(|(prompt, completion)| TokenUsage {
+                    prompt_tokens: prompt.max(0) as u32,
+                    completion_tokens: completion.max(0) as u32,
+                }),
+            cost: row.cost,
+            created_at: row.created_at,
+        }
+    }
+}
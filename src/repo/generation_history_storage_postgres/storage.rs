@@ -0,0 +1,87 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::MessageStore;
+use crate::repo::generation_history_storage_postgres::dto::{HistoryEntry, HistoryEntryRow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use teloxide::types::{ChatId, MessageId};
+
+pub struct PGGenerationHistoryStorage {
+    storage: PgStore,
+}
+
+impl PGGenerationHistoryStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl MessageStore for PGGenerationHistoryStorage {
+    async fn add_message(&self, entry: &HistoryEntry) -> Result<(), ApiError> {
+        sqlx::query(
+            r"insert into generations
+                (id, chat_id, message_id, model, prompt_template, input_text, output_text,
+                 latency_ms, prompt_tokens, completion_tokens, cost, created_at)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12);",
+        )
+        .bind(entry.id)
+        .bind(entry.chat_id.0)
+        .bind(entry.message_id.0)
+        .bind(entry.model.to_string())
+        .bind(&entry.prompt_template)
+        .bind(&entry.input_text)
+        .bind(&entry.output_text)
+        .bind(i32::try_from(entry.latency.as_millis()).unwrap_or(i32::MAX))
+        .bind(
+            entry
+                .usage
+                .map(|u| i32::try_from(u.prompt_tokens).unwrap_or(i32::MAX)),
+        )
+        .bind(
+            entry
+                .usage
+                .map(|u| i32::try_from(u.completion_tokens).unwrap_or(i32::MAX)),
+        )
+        .bind(entry.cost)
+        .bind(entry.created_at)
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(())
+    }
+
+    async fn get_message_info(
+        &self,
+        chat_id: ChatId,
+        message_id: MessageId,
+    ) -> Result<Option<HistoryEntry>, ApiError> {
+        let row = sqlx::query_as::<_, HistoryEntryRow>(
+            r"select id, chat_id, message_id, model, prompt_template, input_text, output_text,
+                     latency_ms, prompt_tokens, completion_tokens, cost, created_at
+                from generations
+                where chat_id = $1 and message_id = $2
+                order by created_at desc
+                limit 1;",
+        )
+        .bind(chat_id.0)
+        .bind(message_id.0)
+        .fetch_optional(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(row.map(HistoryEntry::from))
+    }
+
+    async fn remove_older_than(&self, cutoff: DateTime<Utc>) -> Result<u64, ApiError> {
+        let res = sqlx::query(r"delete from generations where created_at < $1;")
+            .bind(cutoff)
+            .execute(&self.storage.pool)
+            .await
+            .map_err(DBError)?;
+
+        Ok(res.rows_affected())
+    }
+}
@@ -1,4 +1,11 @@
+pub mod chat_history_storage;
+pub mod chat_settings_storage_postgres;
 pub mod dialogue_storage;
+pub mod generation_history_storage_postgres;
 pub mod media_storage;
 pub mod media_storage_postgres;
-pub mod message_history_storage;
+pub mod model_stats_storage_postgres;
+pub mod pinned_countdown_storage_postgres;
+pub mod prompt_template_storage_postgres;
+pub mod subscription_storage_postgres;
+pub mod vote_storage_postgres;
@@ -0,0 +1,135 @@
+use crate::common::Model;
+use crate::generation_controller::TokenUsage;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    Success,
+    Failure,
+    /// The model answered but the text was rejected by the validator.
+    Invalid,
+}
+
+impl AttemptOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttemptOutcome::Success => "success",
+            AttemptOutcome::Failure => "failure",
+            AttemptOutcome::Invalid => "invalid",
+        }
+    }
+}
+
+/// One request to one model, successful or not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationAttempt {
+    pub id: Uuid,
+    pub model: Model,
+    pub outcome: AttemptOutcome,
+    pub latency: Duration,
+    pub usage: Option<TokenUsage>,
+    pub cost: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GenerationAttempt {
+    pub fn new(
+        model: Model,
+        outcome: AttemptOutcome,
+        latency: Duration,
+        usage: Option<TokenUsage>,
+        cost: Option<f64>,
+    ) -> Self {
+        GenerationAttempt {
+            id: Uuid::new_v4(),
+            model,
+            outcome,
+            latency,
+            usage,
+            cost,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Total cost of all attempts of one model over a period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSpending {
+    pub model: Model,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ModelSpendingRow {
+    pub model: String,
+    pub cost: f64,
+}
+
+impl From<ModelSpendingRow> for ModelSpending {
+    fn from(row: ModelSpendingRow) -> Self {
+        ModelSpending {
+            model: Model::new(row.model),
+            cost: row.cost,
+        }
+    }
+}
+
+/// Aggregated reliability and votes of one model over a time window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelLeaderboardEntry {
+    pub model: Model,
+    pub attempts: u64,
+    pub generations: u64,
+    pub validation_failures: u64,
+    pub avg_latency: Option<Duration>,
+    pub vote_score: i64,
+}
+
+impl ModelLeaderboardEntry {
+    pub fn empty(model: Model) -> Self {
+        ModelLeaderboardEntry {
+            model,
+            attempts: 0,
+            generations: 0,
+            validation_failures: 0,
+            avg_latency: None,
+            vote_score: 0,
+        }
+    }
+
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.generations as f64 / self.attempts as f64)
+    }
+
+    pub fn validation_failure_rate(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.validation_failures as f64 / self.attempts as f64)
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ModelLeaderboardRow {
+    pub model: String,
+    pub attempts: i64,
+    pub generations: i64,
+    pub validation_failures: i64,
+    pub avg_latency_ms: Option<f64>,
+    pub vote_score: i64,
+}
+
+impl From<ModelLeaderboardRow> for ModelLeaderboardEntry {
+    fn from(row: ModelLeaderboardRow) -> Self {
+        ModelLeaderboardEntry {
+            model: Model::new(row.model),
+            attempts: row.attempts.max(0) as u64,
+            generations: row.generations.max(0) as u64,
+            validation_failures: row.validation_failures.max(0) as u64,
+            avg_latency: row
+                .avg_latency_ms
+                .map(|ms| Duration::from_secs_f64(ms.max(0.0) / 1000.0)),
+            vote_score: row.vote_score,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::ModelStatsStore;
+use crate::repo::model_stats_storage_postgres::dto::{
+    GenerationAttempt, ModelLeaderboardEntry, ModelLeaderboardRow, ModelSpending, ModelSpendingRow,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+pub struct PGModelStatsStorage {
+    storage: PgStore,
+}
+
+impl PGModelStatsStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl ModelStatsStore for PGModelStatsStorage {
+    async fn record_attempt(&self, attempt: &GenerationAttempt) -> Result<(), ApiError> {
+        sqlx::query(
+            r"insert into generation_attempts
+                (id, model, outcome, latency_ms, prompt_tokens, completion_tokens, cost, created_at)
+                values ($1, $2, $3, $4, $5, $6, $7, $8);",
+        )
+        .bind(attempt.id)
+        .bind(attempt.model.to_string())
+        .bind(attempt.outcome.as_str())
+        .bind(i32::try_from(attempt.latency.as_millis()).unwrap_or(i32::MAX))
+        .bind(
+            attempt
+                .usage
+                .map(|u| i32::try_from(u.prompt_tokens).unwrap_or(i32::MAX)),
+        )
+        .bind(
+            attempt
+                .usage
+                .map(|u| i32::try_from(u.completion_tokens).unwrap_or(i32::MAX)),
+        )
+        .bind(attempt.cost)
+        .bind(attempt.created_at)
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(())
+    }
+
+    async fn leaderboard(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ModelLeaderboardEntry>, ApiError> {
+        let rows = sqlx::query_as::<_, ModelLeaderboardRow>(
+            r"select coalesce(a.model, v.model) as model,
+                     coalesce(a.attempts, 0) as attempts,
+                     coalesce(a.generations, 0) as generations,
+                     coalesce(a.validation_failures, 0) as validation_failures,
+                     a.avg_latency_ms,
+                     coalesce(v.vote_score, 0) as vote_score
+                from (
+                    select model,
+                           count(*) as attempts,
+                           count(*) filter (where outcome = 'success') as generations,
+                           count(*) filter (where outcome = 'invalid') as validation_failures,
+                           (avg(latency_ms) filter (where outcome = 'success'))::float8
+                               as avg_latency_ms
+                      from generation_attempts
+                     where created_at >= $1
+                     group by model
+                ) a
+                full outer join (
+                    select model, sum(value)::bigint as vote_score
+                      from generation_votes
+                     where updated_at >= $1
+                     group by model
+                ) v on v.model = a.model;",
+        )
+        .bind(since)
+        .fetch_all(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(rows.into_iter().map(ModelLeaderboardEntry::from).collect())
+    }
+
+    async fn spending_since(&self, since: DateTime<Utc>) -> Result<Vec<ModelSpending>, ApiError> {
+        let rows = sqlx::query_as::<_, ModelSpendingRow>(
+            r"select model, sum(cost)::float8 as cost
+                from generation_attempts
+                where created_at >= $1 and cost is not null
+                group by model;",
+        )
+        .bind(since)
+        .fetch_all(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(rows.into_iter().map(ModelSpending::from).collect())
+    }
+
+    async fn remove_older_than(&self, cutoff: DateTime<Utc>) -> Result<u64, ApiError> {
+        let res = sqlx::query(r"delete from generation_attempts where created_at < $1;")
+            .bind(cutoff)
+            .execute(&self.storage.pool)
+            .await
+            .map_err(DBError)?;
+
+        Ok(res.rows_affected())
+    }
+}
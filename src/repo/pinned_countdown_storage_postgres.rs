@@ -0,0 +1,72 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::PinnedCountdownStore;
+use async_trait::async_trait;
+use teloxide::types::{ChatId, MessageId};
+
+pub struct PGPinnedCountdownStorage {
+    storage: PgStore,
+}
+
+impl PGPinnedCountdownStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl PinnedCountdownStore for PGPinnedCountdownStorage {
+    async fn save_pinned(&self, chat_id: ChatId, message_id: MessageId) -> Result<(), ApiError> {
+        sqlx::query(
+            r"insert into pinned_countdowns (chat_id, message_id)
+                values ($1, $2)
+                on conflict (chat_id) do update
+                set message_id = excluded.message_id,
+                    updated_at = current_timestamp;",
+        )
+        .bind(chat_id.0)
+        .bind(message_id.0)
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(())
+    }
+
+    async fn get_pinned(&self, chat_id: ChatId) -> Result<Option<MessageId>, ApiError> {
+        let message_id: Option<i32> =
+            sqlx::query_scalar(r"select message_id from pinned_countdowns where chat_id = $1;")
+                .bind(chat_id.0)
+                .fetch_optional(&self.storage.pool)
+                .await
+                .map_err(DBError)?;
+
+        Ok(message_id.map(MessageId))
+    }
+
+    async fn remove_pinned(&self, chat_id: ChatId) -> Result<Option<MessageId>, ApiError> {
+        let message_id: Option<i32> = sqlx::query_scalar(
+            r"delete from pinned_countdowns where chat_id = $1 returning message_id;",
+        )
+        .bind(chat_id.0)
+        .fetch_optional(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(message_id.map(MessageId))
+    }
+
+    async fn list_pinned(&self) -> Result<Vec<(ChatId, MessageId)>, ApiError> {
+        let rows: Vec<(i64, i32)> =
+            sqlx::query_as(r"select chat_id, message_id from pinned_countdowns;")
+                .fetch_all(&self.storage.pool)
+                .await
+                .map_err(DBError)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(chat_id, message_id)| (ChatId(chat_id), MessageId(message_id)))
+            .collect())
+    }
+}
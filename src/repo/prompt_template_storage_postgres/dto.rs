@@ -0,0 +1,64 @@
+use crate::constants::TEXT_MODIFY_PROMPT;
+use sqlx::FromRow;
+
+/// Persona used when a chat has not activated any template.
+pub const DEFAULT_PROMPT_TEMPLATE: &str = "нефор";
+
+pub const TIME_LEFT_VARIABLE: &str = "{time_left}";
+pub const CHAT_TITLE_VARIABLE: &str = "{chat_title}";
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub body: String,
+}
+
+/// Values substituted into the template body.
+#[derive(Debug, Clone, Default)]
+pub struct PromptVariables {
+    pub time_left: String,
+    pub chat_title: String,
+}
+
+impl PromptTemplate {
+    pub fn new(name: impl Into<String>, body: impl Into<String>) -> Self {
+        PromptTemplate {
+            name: name.into(),
+            body: body.into(),
+        }
+    }
+
+    /// The built-in template, compiled into the binary so the bot works with an empty table.
+    pub fn builtin() -> Self {
+        PromptTemplate::new(DEFAULT_PROMPT_TEMPLATE, TEXT_MODIFY_PROMPT)
+    }
+
+    pub fn uses(&self, variable: &str) -> bool {
+        self.body.contains(variable)
+    }
+
+    pub fn render(&self, variables: &PromptVariables) -> String {
+        self.body
+            .replace(TIME_LEFT_VARIABLE, &variables.time_left)
+            .replace(CHAT_TITLE_VARIABLE, &variables.chat_title)
+    }
+}
+
+#[test]
+fn prompt_template_render_test() {
+    let template = PromptTemplate::new(
+        "офисный",
+        "Ты менеджер чата {chat_title}. До пятницы {time_left}, {unknown} не трогай.",
+    );
+    let variables = PromptVariables {
+        time_left: "2 часа".to_string(),
+        chat_title: "Нефоры".to_string(),
+    };
+
+    assert!(template.uses(CHAT_TITLE_VARIABLE));
+    assert_eq!(
+        template.render(&variables),
+        "Ты менеджер чата Нефоры. До пятницы 2 часа, {unknown} не трогай."
+    );
+    assert!(!PromptTemplate::builtin().uses(TIME_LEFT_VARIABLE));
+}
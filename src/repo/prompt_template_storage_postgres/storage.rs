@@ -0,0 +1,78 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::PromptTemplateStore;
+use crate::repo::prompt_template_storage_postgres::dto::PromptTemplate;
+use async_trait::async_trait;
+use teloxide::types::UserId;
+
+pub struct PGPromptTemplateStorage {
+    storage: PgStore,
+}
+
+impl PGPromptTemplateStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl PromptTemplateStore for PGPromptTemplateStorage {
+    async fn create_template(
+        &self,
+        template: &PromptTemplate,
+        user_id: Option<UserId>,
+    ) -> Result<bool, ApiError> {
+        let res = sqlx::query(
+            r"insert into prompt_templates (name, body, created_by)
+                values ($1, $2, $3)
+                on conflict (name) do nothing;",
+        )
+        .bind(&template.name)
+        .bind(&template.body)
+        .bind(user_id.map(|id| id.0 as i64))
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn update_template(&self, template: &PromptTemplate) -> Result<bool, ApiError> {
+        let res = sqlx::query(
+            r"update prompt_templates
+                set body = $2, updated_at = current_timestamp
+                where name = $1;",
+        )
+        .bind(&template.name)
+        .bind(&template.body)
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn get_template(&self, name: &str) -> Result<Option<PromptTemplate>, ApiError> {
+        let template = sqlx::query_as::<_, PromptTemplate>(
+            r"select name, body from prompt_templates where name = $1;",
+        )
+        .bind(name)
+        .fetch_optional(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(template)
+    }
+
+    async fn list_templates(&self) -> Result<Vec<PromptTemplate>, ApiError> {
+        let templates = sqlx::query_as::<_, PromptTemplate>(
+            r"select name, body from prompt_templates order by name;",
+        )
+        .fetch_all(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(templates)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::SubscriptionStore;
+use async_trait::async_trait;
+use teloxide::types::{ChatId, UserId};
+
+pub struct PGSubscriptionStorage {
+    storage: PgStore,
+}
+
+impl PGSubscriptionStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl SubscriptionStore for PGSubscriptionStorage {
+    async fn subscribe(&self, chat_id: ChatId, user_id: Option<UserId>) -> Result<bool, ApiError> {
+        let res = sqlx::query(
+            r"insert into chat_subscriptions (chat_id, subscribed_by)
+                values ($1, $2)
+                on conflict (chat_id) do nothing;",
+        )
+        .bind(chat_id.0)
+        .bind(user_id.map(|id| id.0 as i64))
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    async fn unsubscribe(&self, chat_id: ChatId) -> Result<bool, ApiError> {
+        let res = sqlx::query(r"delete from chat_subscriptions where chat_id = $1;")
+            .bind(chat_id.0)
+            .execute(&self.storage.pool)
+            .await
+            .map_err(DBError)?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    async fn list_subscribed_chats(&self) -> Result<Vec<ChatId>, ApiError> {
+        let chat_ids: Vec<i64> = sqlx::query_scalar(r"select chat_id from chat_subscriptions;")
+            .fetch_all(&self.storage.pool)
+            .await
+            .map_err(DBError)?;
+
+        Ok(chat_ids.into_iter().map(ChatId).collect())
+    }
+}
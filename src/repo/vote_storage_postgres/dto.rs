@@ -0,0 +1,44 @@
+use crate::common::Model;
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
+use teloxide::types::{ChatId, UserId};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteValue {
+    Up,
+    Down,
+}
+
+impl VoteValue {
+    pub fn score(self) -> i16 {
+        match self {
+            VoteValue::Up => 1,
+            VoteValue::Down => -1,
+        }
+    }
+}
+
+/// User reaction to one generation. Model and prompt are copied from the generation
+/// so the votes outlive the history retention.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vote {
+    pub generation_id: Uuid,
+    pub user_id: UserId,
+    pub chat_id: ChatId,
+    pub model: Model,
+    pub prompt_template: String,
+    pub value: VoteValue,
+}
+
+impl Vote {
+    pub fn new(entry: &HistoryEntry, user_id: UserId, value: VoteValue) -> Self {
+        Vote {
+            generation_id: entry.id,
+            user_id,
+            chat_id: entry.chat_id,
+            model: entry.model.clone(),
+            prompt_template: entry.prompt_template.clone(),
+            value,
+        }
+    }
+}
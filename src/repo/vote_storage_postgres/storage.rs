@@ -0,0 +1,41 @@
+use crate::adapter::postgres::PgStore;
+use crate::errors::ApiError;
+use crate::errors::RepoError::DBError;
+use crate::handlers::root_handler::VoteStore;
+use crate::repo::vote_storage_postgres::dto::Vote;
+use async_trait::async_trait;
+
+pub struct PGVoteStorage {
+    storage: PgStore,
+}
+
+impl PGVoteStorage {
+    pub fn new(pool: PgStore) -> Self {
+        Self { storage: pool }
+    }
+}
+
+#[async_trait]
+impl VoteStore for PGVoteStorage {
+    async fn vote(&self, vote: &Vote) -> Result<(), ApiError> {
+        sqlx::query(
+            r"insert into generation_votes
+                (generation_id, user_id, chat_id, model, prompt_template, value)
+                values ($1, $2, $3, $4, $5, $6)
+                on conflict (generation_id, user_id) do update
+                set value = excluded.value,
+                    updated_at = current_timestamp;",
+        )
+        .bind(vote.generation_id)
+        .bind(vote.user_id.0 as i64)
+        .bind(vote.chat_id.0)
+        .bind(vote.model.to_string())
+        .bind(&vote.prompt_template)
+        .bind(vote.value.score())
+        .execute(&self.storage.pool)
+        .await
+        .map_err(DBError)?;
+
+        Ok(())
+    }
+}
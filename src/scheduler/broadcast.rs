@@ -0,0 +1,93 @@
+use crate::handlers::friday::friday;
+use crate::handlers::root_handler::{
+    ChatSettingsStore, ContentGenerator, MessageStore, PromptTemplateStore, SubscriptionStore,
+};
+use crate::scheduler::MinuteTicker;
+use crate::scheduler::schedule::BroadcastSchedule;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use teloxide::Bot;
+use tracing::{error, info, instrument};
+
+pub struct FridayBroadcaster {
+    bot: Bot,
+    generator: Arc<dyn ContentGenerator>,
+    message_store: Arc<dyn MessageStore>,
+    subscriptions: Arc<dyn SubscriptionStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    schedule: BroadcastSchedule,
+}
+
+impl FridayBroadcaster {
+    pub fn new(
+        bot: Bot,
+        generator: Arc<dyn ContentGenerator>,
+        message_store: Arc<dyn MessageStore>,
+        subscriptions: Arc<dyn SubscriptionStore>,
+        settings_store: Arc<dyn ChatSettingsStore>,
+        prompts: Arc<dyn PromptTemplateStore>,
+        schedule: BroadcastSchedule,
+    ) -> Self {
+        FridayBroadcaster {
+            bot,
+            generator,
+            message_store,
+            subscriptions,
+            settings_store,
+            prompts,
+            schedule,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut ticker = MinuteTicker::new();
+        loop {
+            for minute in ticker.tick().await {
+                self.broadcast(minute).await;
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn broadcast(&self, now: DateTime<Utc>) {
+        let chats = match self.subscriptions.list_subscribed_chats().await {
+            Ok(chats) => chats,
+            Err(e) => {
+                error!(error = %e, "Failed to list subscribed chats");
+                return;
+            }
+        };
+
+        for chat_id in chats {
+            let settings = match self.settings_store.get_settings(chat_id).await {
+                Ok(settings) => settings,
+                Err(e) => {
+                    error!(error = %e, %chat_id, "Failed to get chat settings");
+                    continue;
+                }
+            };
+
+            if !self.schedule.is_due(&settings, now) {
+                continue;
+            }
+
+            info!(%chat_id, "Broadcasting friday countdown");
+
+            // Sent in the background, so a slow model doesn't delay the other chats
+            let send = friday(
+                self.bot.clone(),
+                chat_id,
+                self.generator.clone(),
+                self.message_store.clone(),
+                self.settings_store.clone(),
+                self.prompts.clone(),
+            );
+            tokio::spawn(async move {
+                if let Err(e) = send.await {
+                    error!(error = %e, %chat_id, "Failed to broadcast friday countdown");
+                }
+            });
+        }
+    }
+}
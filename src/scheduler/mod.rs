@@ -0,0 +1,144 @@
+pub mod broadcast;
+pub mod pinned_countdown;
+pub mod pregeneration;
+pub mod retention;
+pub mod schedule;
+
+use chrono::{DateTime, DurationRound, TimeDelta, Timelike, Utc};
+use dashmap::DashSet;
+use std::iter::successors;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::types::ChatId;
+
+/// Longest run of missed minutes that is caught up, e.g. after the host was suspended.
+const MAX_CATCH_UP_MINUTES: i64 = 60;
+
+pub async fn sleep_until_next_minute() {
+    let now = Utc::now();
+    let elapsed = Duration::new(now.second() as u64, now.nanosecond() % 1_000_000_000);
+
+    tokio::time::sleep(Duration::from_secs(60).saturating_sub(elapsed)).await;
+}
+
+/// Wakes up every minute and tells which minutes have passed since the previous tick,
+/// so a run that overslept a minute boundary doesn't skip what was due at it.
+pub struct MinuteTicker {
+    last: DateTime<Utc>,
+}
+
+impl MinuteTicker {
+    pub fn new() -> Self {
+        MinuteTicker {
+            last: start_of_minute(Utc::now()),
+        }
+    }
+
+    /// Starts of the minutes passed since the previous tick, oldest first.
+    pub async fn tick(&mut self) -> Vec<DateTime<Utc>> {
+        sleep_until_next_minute().await;
+        self.passed(Utc::now())
+    }
+
+    fn passed(&mut self, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let now = start_of_minute(now);
+        let first = (self.last + TimeDelta::minutes(1))
+            .max(now - TimeDelta::minutes(MAX_CATCH_UP_MINUTES - 1));
+        self.last = self.last.max(now);
+
+        successors(Some(first), |minute| Some(*minute + TimeDelta::minutes(1)))
+            .take_while(|minute| *minute <= now)
+            .collect()
+    }
+}
+
+fn start_of_minute(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.duration_trunc(TimeDelta::minutes(1))
+        .expect("minutes fit any timestamp")
+}
+
+/// Per-chat jobs running in the background, so one slow model doesn't hold up the whole loop.
+#[derive(Clone, Default)]
+pub struct ChatJobs {
+    running: Arc<DashSet<ChatId>>,
+}
+
+impl ChatJobs {
+    /// Spawns the job unless the chat still has one running from an earlier minute.
+    pub fn spawn<F>(&self, chat_id: ChatId, job: F) -> bool
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if !self.running.insert(chat_id) {
+            return false;
+        }
+
+        let finished = JobFinished {
+            running: self.running.clone(),
+            chat_id,
+        };
+        tokio::spawn(async move {
+            let _finished = finished;
+            job.await;
+        });
+
+        true
+    }
+}
+
+/// Releases the chat even when the job panics.
+struct JobFinished {
+    running: Arc<DashSet<ChatId>>,
+    chat_id: ChatId,
+}
+
+impl Drop for JobFinished {
+    fn drop(&mut self) {
+        self.running.remove(&self.chat_id);
+    }
+}
+
+#[test]
+fn minute_ticker_test() {
+    use chrono::TimeZone;
+
+    let at = |hour, minute, second| {
+        Utc.with_ymd_and_hms(2026, 10, 23, hour, minute, second)
+            .unwrap()
+    };
+    let mut ticker = MinuteTicker { last: at(0, 0, 0) };
+
+    assert_eq!(ticker.passed(at(0, 1, 0)), [at(0, 1, 0)]);
+    // Woken up a bit early, nothing has passed yet
+    assert!(ticker.passed(at(0, 1, 59)).is_empty());
+
+    // A slow run overslept two minute boundaries
+    assert_eq!(
+        ticker.passed(at(0, 4, 3)),
+        [at(0, 2, 0), at(0, 3, 0), at(0, 4, 0)]
+    );
+
+    let passed = ticker.passed(at(12, 0, 0));
+    assert_eq!(passed.len(), MAX_CATCH_UP_MINUTES as usize);
+    assert_eq!(passed.last(), Some(&at(12, 0, 0)));
+}
+
+#[tokio::test]
+async fn chat_jobs_test() {
+    use tokio::sync::oneshot;
+
+    let jobs = ChatJobs::default();
+    let (release, released) = oneshot::channel::<()>();
+
+    assert!(jobs.spawn(ChatId(1), async {
+        let _ = released.await;
+    }));
+    assert!(!jobs.spawn(ChatId(1), async {}));
+    assert!(jobs.spawn(ChatId(2), async {}));
+
+    release.send(()).unwrap();
+    while jobs.running.contains(&ChatId(1)) {
+        tokio::task::yield_now().await;
+    }
+    assert!(jobs.spawn(ChatId(1), async {}));
+}
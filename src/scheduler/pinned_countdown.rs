@@ -0,0 +1,174 @@
+use crate::errors::ApiError;
+use crate::handlers::friday::{friday_text, generation_request};
+use crate::handlers::pinned_countdown::pinned_countdown_text;
+use crate::handlers::root_handler::{
+    ChatSettingsStore, ContentGenerator, MessageStore, PinnedCountdownStore, PromptTemplateStore,
+};
+use crate::repo::chat_settings_storage_postgres::dto::ChatSettings;
+use crate::repo::generation_history_storage_postgres::dto::HistoryEntry;
+use crate::scheduler::{ChatJobs, MinuteTicker};
+use crate::utils::{FridayStatus, get_friday_status};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::MessageId;
+use teloxide::{ApiError as TelegramApiError, Bot, RequestError};
+use tracing::{error, info, instrument, warn};
+
+pub struct PinnedCountdownUpdater {
+    bot: Bot,
+    generator: Arc<dyn ContentGenerator>,
+    message_store: Arc<dyn MessageStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    pinned_store: Arc<dyn PinnedCountdownStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    jobs: ChatJobs,
+}
+
+impl PinnedCountdownUpdater {
+    pub fn new(
+        bot: Bot,
+        generator: Arc<dyn ContentGenerator>,
+        message_store: Arc<dyn MessageStore>,
+        settings_store: Arc<dyn ChatSettingsStore>,
+        pinned_store: Arc<dyn PinnedCountdownStore>,
+        prompts: Arc<dyn PromptTemplateStore>,
+    ) -> Self {
+        PinnedCountdownUpdater {
+            bot,
+            generator,
+            message_store,
+            settings_store,
+            pinned_store,
+            prompts,
+            jobs: ChatJobs::default(),
+        }
+    }
+
+    pub async fn run(self) {
+        let this = Arc::new(self);
+        let mut ticker = MinuteTicker::new();
+        loop {
+            // Edits show the current countdown, missed minutes only matter for the hourly ones
+            let passed = ticker.tick().await.len() as i64;
+            if passed == 0 {
+                continue;
+            }
+
+            let pinned = match this.pinned_store.list_pinned().await {
+                Ok(pinned) => pinned,
+                Err(e) => {
+                    error!(error = %e, "Failed to list pinned countdowns");
+                    continue;
+                }
+            };
+
+            for (chat_id, message_id) in pinned {
+                let updater = this.clone();
+                this.jobs.spawn(chat_id, async move {
+                    if let Err(e) = updater.update(chat_id, message_id, passed).await {
+                        error!(error = %e, %chat_id, "Failed to update pinned countdown");
+                    }
+                });
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn update(
+        &self,
+        chat_id: ChatId,
+        message_id: MessageId,
+        passed: i64,
+    ) -> Result<(), ApiError> {
+        let settings = self.settings_store.get_settings(chat_id).await?;
+
+        match get_friday_status(&settings, Utc::now()) {
+            FridayStatus::Countdown(time_left) => {
+                if !should_update(time_left, passed) {
+                    return Ok(());
+                }
+
+                let result = self
+                    .bot
+                    .edit_message_text(chat_id, message_id, pinned_countdown_text(time_left))
+                    .await;
+
+                match result {
+                    Ok(_) | Err(RequestError::Api(TelegramApiError::MessageNotModified)) => {}
+                    Err(RequestError::Api(
+                        TelegramApiError::MessageToEditNotFound
+                        | TelegramApiError::MessageCantBeEdited,
+                    )) => {
+                        warn!("Pinned countdown message is gone, forgetting it");
+                        self.pinned_store.remove_pinned(chat_id).await?;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            status => self.announce(&settings, message_id, status).await?,
+        }
+
+        Ok(())
+    }
+
+    async fn announce(
+        &self,
+        settings: &ChatSettings,
+        message_id: MessageId,
+        status: FridayStatus,
+    ) -> Result<(), ApiError> {
+        let chat_id = settings.chat_id;
+        let text = friday_text(status);
+        let request =
+            generation_request(&self.bot, settings, self.prompts.as_ref(), status).await?;
+
+        let generation = match self.generator.generate_text(&request).await {
+            Ok(generation) => Some(generation),
+            Err(err) => {
+                error!(error = %err, "Failed to rephrase text");
+                None
+            }
+        };
+
+        let text = generation.as_ref().map_or(text, |g| g.text.clone());
+
+        self.pinned_store.remove_pinned(chat_id).await?;
+        self.bot
+            .edit_message_text(chat_id, message_id, text)
+            .await?;
+
+        if let Some(generation) = generation {
+            let entry = HistoryEntry::new(chat_id, message_id, &request, &generation);
+            if let Err(e) = self.message_store.add_message(&entry).await {
+                error!(error = %e, "Failed to save generation history");
+            }
+        }
+
+        info!("Pinned countdown finished with announcement");
+        Ok(())
+    }
+}
+
+/// Edits every minute during the last day and once an hour before that, also when
+/// the hour mark fell on one of the `passed` minutes since the previous run.
+fn should_update(time_left: Duration, passed: i64) -> bool {
+    let minutes_left = (time_left + Duration::seconds(30)).num_minutes();
+
+    minutes_left < 24 * 60 || (0..passed).any(|missed| (minutes_left + missed) % 60 == 0)
+}
+
+#[test]
+fn pinned_countdown_should_update_test() {
+    assert!(should_update(
+        Duration::hours(23) + Duration::minutes(17),
+        1
+    ));
+    assert!(should_update(Duration::days(2) + Duration::seconds(10), 1));
+    assert!(!should_update(Duration::days(2) + Duration::minutes(17), 1));
+
+    // The hour mark was two minutes ago, while the previous run was still busy
+    assert!(!should_update(Duration::days(2) - Duration::minutes(2), 1));
+    assert!(should_update(Duration::days(2) - Duration::minutes(2), 3));
+}
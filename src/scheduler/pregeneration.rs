@@ -0,0 +1,120 @@
+use crate::errors::ApiError;
+use crate::generation_controller::cache::PregenerateConfig;
+use crate::handlers::friday::generation_request;
+use crate::handlers::root_handler::{
+    ChatSettingsStore, ContentGenerator, PromptTemplateStore, SubscriptionStore,
+};
+use crate::scheduler::{ChatJobs, MinuteTicker};
+use crate::utils::{FridayStatus, get_friday_status};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use teloxide::Bot;
+use teloxide::prelude::ChatId;
+use tracing::{error, info, instrument};
+
+/// Fills the buffer of "it's Friday" texts for subscribed chats shortly before the start,
+/// so the announcement is sent as soon as Friday begins.
+pub struct FridayPregenerator {
+    bot: Bot,
+    generator: Arc<dyn ContentGenerator>,
+    subscriptions: Arc<dyn SubscriptionStore>,
+    settings_store: Arc<dyn ChatSettingsStore>,
+    prompts: Arc<dyn PromptTemplateStore>,
+    lead: Duration,
+    jobs: ChatJobs,
+}
+
+impl FridayPregenerator {
+    pub fn new(
+        bot: Bot,
+        generator: Arc<dyn ContentGenerator>,
+        subscriptions: Arc<dyn SubscriptionStore>,
+        settings_store: Arc<dyn ChatSettingsStore>,
+        prompts: Arc<dyn PromptTemplateStore>,
+        config: &PregenerateConfig,
+    ) -> Self {
+        FridayPregenerator {
+            bot,
+            generator,
+            subscriptions,
+            settings_store,
+            prompts,
+            lead: Duration::minutes(config.lead_minutes as i64),
+            jobs: ChatJobs::default(),
+        }
+    }
+
+    pub async fn run(self) {
+        let this = Arc::new(self);
+        let mut ticker = MinuteTicker::new();
+        loop {
+            // Only the latest minute matters, the buffer is filled anywhere within the lead
+            if let Some(now) = ticker.tick().await.pop() {
+                this.pregenerate(now).await;
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn pregenerate(self: &Arc<Self>, now: DateTime<Utc>) {
+        let chats = match self.subscriptions.list_subscribed_chats().await {
+            Ok(chats) => chats,
+            Err(e) => {
+                error!(error = %e, "Failed to list subscribed chats");
+                return;
+            }
+        };
+
+        for chat_id in chats {
+            let this = self.clone();
+            self.jobs.spawn(chat_id, async move {
+                if let Err(e) = this.pregenerate_chat(chat_id, now).await {
+                    error!(error = %e, %chat_id, "Failed to pregenerate friday texts");
+                }
+            });
+        }
+    }
+
+    async fn pregenerate_chat(&self, chat_id: ChatId, now: DateTime<Utc>) -> Result<(), ApiError> {
+        let settings = self.settings_store.get_settings(chat_id).await?;
+        if !starts_soon(get_friday_status(&settings, now), self.lead) {
+            return Ok(());
+        }
+
+        let request = generation_request(
+            &self.bot,
+            &settings,
+            self.prompts.as_ref(),
+            FridayStatus::Started,
+        )
+        .await?;
+
+        let added = self.generator.pregenerate(&request).await?;
+        if added > 0 {
+            info!(%chat_id, added, "Pregenerated friday texts");
+        }
+
+        Ok(())
+    }
+}
+
+fn starts_soon(status: FridayStatus, lead: Duration) -> bool {
+    matches!(status, FridayStatus::Countdown(time_left) if time_left <= lead)
+}
+
+#[test]
+fn starts_soon_test() {
+    let lead = Duration::minutes(60);
+
+    assert!(starts_soon(
+        FridayStatus::Countdown(Duration::minutes(5)),
+        lead
+    ));
+    assert!(starts_soon(FridayStatus::Countdown(lead), lead));
+    assert!(!starts_soon(
+        FridayStatus::Countdown(Duration::minutes(61)),
+        lead
+    ));
+    assert!(!starts_soon(FridayStatus::Started, lead));
+    assert!(!starts_soon(FridayStatus::Over, lead));
+}
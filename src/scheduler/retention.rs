@@ -0,0 +1,48 @@
+use crate::handlers::root_handler::{MessageStore, ModelStatsStore};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info};
+
+const CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Removes generation history and model attempts older than the configured retention period.
+pub struct HistoryRetention {
+    message_store: Arc<dyn MessageStore>,
+    stats_store: Arc<dyn ModelStatsStore>,
+    retention: Duration,
+}
+
+impl HistoryRetention {
+    pub fn new(
+        message_store: Arc<dyn MessageStore>,
+        stats_store: Arc<dyn ModelStatsStore>,
+        retention_days: u32,
+    ) -> Self {
+        HistoryRetention {
+            message_store,
+            stats_store,
+            retention: Duration::days(retention_days.into()),
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let cutoff = Utc::now() - self.retention;
+            match self.message_store.remove_older_than(cutoff).await {
+                Ok(0) => {}
+                Ok(removed) => info!(removed, %cutoff, "Removed old generation history"),
+                Err(e) => error!(error = %e, "Failed to remove old generation history"),
+            }
+
+            match self.stats_store.remove_older_than(cutoff).await {
+                Ok(0) => {}
+                Ok(removed) => info!(removed, %cutoff, "Removed old generation attempts"),
+                Err(e) => error!(error = %e, "Failed to remove old generation attempts"),
+            }
+        }
+    }
+}
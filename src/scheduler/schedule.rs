@@ -0,0 +1,101 @@
+use crate::errors::BotConfigError;
+use crate::errors::BotConfigError::ParseBroadcastScheduleError;
+use crate::repo::chat_settings_storage_postgres::dto::ChatSettings;
+use chrono::{DateTime, Datelike, NaiveTime, Timelike, Utc, Weekday};
+use std::str::FromStr;
+
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Describes when subscribed chats receive the countdown, in each chat's local time.
+/// The announcement at the chat's Friday start is always sent, regardless of configured days.
+#[derive(Debug, Clone)]
+pub struct BroadcastSchedule {
+    days: Vec<Weekday>,
+    times: Vec<NaiveTime>,
+}
+
+impl BroadcastSchedule {
+    pub fn new(days: Vec<Weekday>, times: Vec<NaiveTime>) -> Self {
+        BroadcastSchedule { days, times }
+    }
+
+    /// Parses comma separated lists like `mon,tue,wed,thu` and `10:00,18:30`.
+    pub fn parse(days: &str, times: &str) -> Result<Self, BotConfigError> {
+        let days = split_list(days)
+            .map(|d| Weekday::from_str(d).map_err(|_| ParseBroadcastScheduleError(d.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let times = split_list(times)
+            .map(|t| {
+                NaiveTime::parse_from_str(t, TIME_FORMAT)
+                    .map_err(|_| ParseBroadcastScheduleError(t.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(BroadcastSchedule::new(days, times))
+    }
+
+    pub fn is_due(&self, settings: &ChatSettings, now: DateTime<Utc>) -> bool {
+        let now = now.with_timezone(&settings.timezone);
+
+        if now.weekday() == settings.weekday
+            && now.hour() == settings.start_time.hour()
+            && now.minute() == settings.start_time.minute()
+        {
+            return true;
+        }
+
+        self.days.contains(&now.weekday())
+            && self
+                .times
+                .iter()
+                .any(|t| t.hour() == now.hour() && t.minute() == now.minute())
+    }
+}
+
+fn split_list(raw: &str) -> impl Iterator<Item = &str> {
+    raw.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+#[test]
+fn broadcast_schedule_parse_test() {
+    let schedule = BroadcastSchedule::parse("mon, thu", "10:00,18:30").unwrap();
+
+    assert_eq!(schedule.days, vec![Weekday::Mon, Weekday::Thu]);
+    assert_eq!(schedule.times.len(), 2);
+    assert!(BroadcastSchedule::parse("someday", "10:00").is_err());
+    assert!(BroadcastSchedule::parse("mon", "25:00").is_err());
+}
+
+#[test]
+fn broadcast_schedule_is_due_test() {
+    use chrono::TimeZone;
+    use chrono_tz::Asia::Yekaterinburg;
+    use chrono_tz::Europe::Moscow;
+    use teloxide::types::ChatId;
+
+    let schedule = BroadcastSchedule::parse("mon,tue,wed,thu", "10:00").unwrap();
+    let settings = ChatSettings::new(ChatId(1));
+
+    // 2026-10-19 is a Monday, 2026-10-23 is a Friday
+    let monday_morning = Moscow.with_ymd_and_hms(2026, 10, 19, 10, 0, 30).unwrap();
+    let monday_later = Moscow.with_ymd_and_hms(2026, 10, 19, 10, 1, 0).unwrap();
+    let friday_midnight = Moscow.with_ymd_and_hms(2026, 10, 23, 0, 0, 0).unwrap();
+    let friday_morning = Moscow.with_ymd_and_hms(2026, 10, 23, 10, 0, 0).unwrap();
+
+    assert!(schedule.is_due(&settings, monday_morning.to_utc()));
+    assert!(!schedule.is_due(&settings, monday_later.to_utc()));
+    assert!(schedule.is_due(&settings, friday_midnight.to_utc()));
+    assert!(!schedule.is_due(&settings, friday_morning.to_utc()));
+
+    let mut custom = settings.clone();
+    custom.timezone = Yekaterinburg;
+    custom.start_time = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+
+    let friday_evening = Yekaterinburg
+        .with_ymd_and_hms(2026, 10, 23, 18, 0, 0)
+        .unwrap();
+
+    assert!(!schedule.is_due(&custom, friday_midnight.to_utc()));
+    assert!(schedule.is_due(&custom, friday_evening.to_utc()));
+}
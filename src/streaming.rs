@@ -0,0 +1,94 @@
+//! Helpers for reading streamed model answers: server-sent events (OpenAI-style APIs,
+//! GigaChat) and newline delimited JSON (Ollama).
+
+use crate::errors::ApiError;
+use crate::errors::ApiError::DecodeResponseError;
+use reqwest::Response;
+
+/// Marks the end of an OpenAI-style event stream.
+pub const SSE_DONE: &str = "[DONE]";
+
+/// Splits a byte stream into lines, chunks may end in the middle of a line or a UTF-8 char.
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Appends a chunk and returns the non-empty lines it completed.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+
+        lines
+    }
+
+    /// Last line when the body doesn't end with a line break.
+    pub fn rest(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&self.buffer).trim().to_string();
+        self.buffer.clear();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Payload of an SSE `data:` line, other fields and comments are of no interest.
+pub fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim)
+}
+
+/// Feeds every line of the body to `on_line` until it reports the end of the answer.
+/// Returns false when the body ended without such a line.
+pub async fn read_lines(
+    mut response: Response,
+    mut on_line: impl FnMut(&str) -> Result<bool, ApiError>,
+) -> Result<bool, ApiError> {
+    let mut buffer = LineBuffer::default();
+
+    while let Some(chunk) = response.chunk().await.map_err(DecodeResponseError)? {
+        for line in buffer.push(&chunk) {
+            if on_line(&line)? {
+                return Ok(true);
+            }
+        }
+    }
+
+    match buffer.rest() {
+        Some(line) => on_line(&line),
+        None => Ok(false),
+    }
+}
+
+#[test]
+fn line_buffer_test() {
+    let mut buffer = LineBuffer::default();
+
+    assert!(buffer.push(b"{\"done\":").is_empty());
+    assert_eq!(
+        buffer.push(b"false}\n\n{\"done\":true}\r\n{\"do"),
+        ["{\"done\":false}", "{\"done\":true}"]
+    );
+    assert_eq!(buffer.push("ne\":true}".as_bytes()), Vec::<String>::new());
+    assert_eq!(buffer.rest().as_deref(), Some("{\"done\":true}"));
+    assert_eq!(buffer.rest(), None);
+
+    // A multibyte char split between chunks
+    let text = "data: пятница\n".as_bytes();
+    assert!(buffer.push(&text[..8]).is_empty());
+    assert_eq!(buffer.push(&text[8..]), ["data: пятница"]);
+}
+
+#[test]
+fn sse_data_test() {
+    assert_eq!(sse_data("data: {\"a\": 1}"), Some("{\"a\": 1}"));
+    assert_eq!(sse_data("data:[DONE]"), Some(SSE_DONE));
+    assert_eq!(sse_data(": keep-alive"), None);
+    assert_eq!(sse_data("event: message"), None);
+}
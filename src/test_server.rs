@@ -0,0 +1,226 @@
+//! In-process HTTP server imitating chat completions and OAuth endpoints, so provider
+//! clients can be tested against real HTTP without network access.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone)]
+pub struct FakeResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl FakeResponse {
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        FakeResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Successful chat completion with a single choice and token usage.
+    pub fn completion(text: &str) -> Self {
+        let body = serde_json::json!({
+            "id": "fake-completion",
+            "object": "chat.completion",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": text},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 7, "total_tokens": 19}
+        });
+
+        FakeResponse::json(200, body.to_string())
+    }
+
+    /// Server-sent events stream of completion deltas, the last event carries token usage.
+    pub fn stream(parts: &[&str]) -> Self {
+        let mut body = String::new();
+        for part in parts {
+            let chunk = serde_json::json!({
+                "object": "chat.completion.chunk",
+                "choices": [{"index": 0, "delta": {"role": "assistant", "content": part}}]
+            });
+            body.push_str(&format!("data: {}\n\n", chunk));
+        }
+
+        let usage = serde_json::json!({
+            "object": "chat.completion.chunk",
+            "choices": [{"index": 0, "delta": {}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 7, "total_tokens": 19}
+        });
+        body.push_str(&format!("data: {}\n\ndata: [DONE]\n\n", usage));
+
+        FakeResponse::json(200, body)
+    }
+
+    /// GigaChat OAuth answer with a token valid for half an hour.
+    pub fn access_token(token: &str) -> Self {
+        let expires_at = SystemTime::now() + Duration::from_secs(30 * 60);
+        let body = serde_json::json!({
+            "access_token": token,
+            "expires_at": expires_at.duration_since(UNIX_EPOCH).unwrap().as_millis() as i64
+        });
+
+        FakeResponse::json(200, body.to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+type Routes = Arc<Mutex<HashMap<String, VecDeque<FakeResponse>>>>;
+
+pub struct FakeServer {
+    url: String,
+    routes: Routes,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    task: JoinHandle<()>,
+}
+
+impl FakeServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let routes: Routes = Arc::default();
+        let requests: Arc<Mutex<Vec<RecordedRequest>>> = Arc::default();
+
+        let task = {
+            let routes = routes.clone();
+            let requests = requests.clone();
+
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let routes = routes.clone();
+                    let requests = requests.clone();
+                    tokio::spawn(async move {
+                        let _ = serve(stream, routes, requests).await;
+                    });
+                }
+            })
+        };
+
+        FakeServer {
+            url,
+            routes,
+            requests,
+            task,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Queues responses for the path, the last one is repeated for all further requests.
+    pub fn respond(&self, path: &str, responses: impl IntoIterator<Item = FakeResponse>) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), responses.into_iter().collect());
+    }
+
+    pub fn requests(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.path == path)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Handles one request per connection, which is enough for reqwest clients.
+async fn serve(
+    stream: TcpStream,
+    routes: Routes,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let response = {
+        let mut routes = routes.lock().unwrap();
+        match routes.get_mut(&path) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        }
+    }
+    .unwrap_or_else(|| FakeResponse::json(404, r#"{"error": "not found"}"#));
+
+    requests.lock().unwrap().push(RecordedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    });
+
+    let mut raw = format!(
+        "HTTP/1.1 {} Fake\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    raw.push_str("\r\n");
+    raw.push_str(&response.body);
+
+    let mut stream = reader.into_inner();
+    stream.write_all(raw.as_bytes()).await?;
+    stream.shutdown().await
+}
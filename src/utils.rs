@@ -1,5 +1,6 @@
-use chrono::{Datelike, Duration, Utc, Weekday};
-use chrono_tz::Europe::Moscow;
+use crate::repo::chat_settings_storage_postgres::dto::ChatSettings;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use std::fmt::Display;
 use teloxide::types::{
     InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup, ReplyMarkup,
@@ -8,28 +9,59 @@ use teloxide::types::{
 const DEFAULT_REPLY_KEYBOARD_CHUNK_SIZE: usize = 3;
 const DEFAULT_INLINE_KEYBOARD_CHUNK_SIZE: usize = 4;
 
-pub fn get_time_until_friday() -> Option<Duration> {
-    let now = Utc::now().with_timezone(&Moscow);
-    let current_weekday = now.weekday();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FridayStatus {
+    Countdown(Duration),
+    Started,
+    Over,
+}
 
-    let days_to_add =
-        (Weekday::Fri.number_from_monday() + 7 - current_weekday.number_from_monday()) % 7;
-    if days_to_add == 0 {
-        return None;
+pub fn get_friday_status(settings: &ChatSettings, now: DateTime<Utc>) -> FridayStatus {
+    let tz = settings.timezone;
+    let local_now = now.with_timezone(&tz);
+
+    let days_since_start = (local_now.weekday().num_days_from_monday() + 7
+        - settings.weekday.num_days_from_monday())
+        % 7;
+    let start_date = local_now.date_naive() - Duration::days(days_since_start as i64);
+
+    let end_date = if settings.end_time > settings.start_time {
+        start_date
+    } else {
+        start_date + Duration::days(1)
+    };
+
+    let start = localize(tz, start_date.and_time(settings.start_time));
+    let end = localize(tz, end_date.and_time(settings.end_time));
+
+    if local_now < start {
+        return FridayStatus::Countdown(start.signed_duration_since(local_now));
     }
 
-    let next_friday_date = now.date_naive() + Duration::days(days_to_add as i64);
-    let next_friday_midnight = next_friday_date.and_hms_opt(0, 0, 0).unwrap();
-    let duration = next_friday_midnight.signed_duration_since(now.naive_local());
+    if local_now < end {
+        return FridayStatus::Started;
+    }
 
-    Some(duration)
+    if days_since_start == 0 {
+        return FridayStatus::Over;
+    }
+
+    let next_start = localize(
+        tz,
+        (start_date + Duration::days(7)).and_time(settings.start_time),
+    );
+    FridayStatus::Countdown(next_start.signed_duration_since(local_now))
 }
 
-pub fn format_time_delta(td: Duration) -> String {
-    let days = td.num_days();
-    let hours = td.num_hours() % 24;
-    let minutes = td.num_minutes() % 60;
-    format!("{days} дней, {hours} часов, {minutes} минут")
+/// Local times skipped by a daylight saving jump are moved forward by the jump, like the clocks.
+fn localize(tz: Tz, naive: NaiveDateTime) -> DateTime<Tz> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| tz.from_utc_datetime(&naive))
 }
 
 pub fn setup_inline_callback_keyboard<T: Display>(data: &[T]) -> Option<InlineKeyboardMarkup> {
@@ -71,4 +103,91 @@ pub fn reply_suggestions_keyboard<T: ToString>(data: &[T], cmd_prefix: &str) ->
     keyboard.resize_keyboard = true;
 
     ReplyMarkup::Keyboard(keyboard)
-}
\ No newline at end of file
+}
+
+#[test]
+fn friday_status_default_settings_test() {
+    use chrono_tz::Europe::Moscow;
+    use teloxide::types::ChatId;
+
+    let settings = ChatSettings::new(ChatId(1));
+
+    // 2026-10-22 is a Thursday, 2026-10-23 is a Friday
+    let thursday = Moscow.with_ymd_and_hms(2026, 10, 22, 22, 30, 0).unwrap();
+    let friday = Moscow.with_ymd_and_hms(2026, 10, 23, 12, 0, 0).unwrap();
+    let saturday = Moscow.with_ymd_and_hms(2026, 10, 24, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        get_friday_status(&settings, thursday.to_utc()),
+        FridayStatus::Countdown(Duration::minutes(90))
+    );
+    assert_eq!(
+        get_friday_status(&settings, friday.to_utc()),
+        FridayStatus::Started
+    );
+    assert_eq!(
+        get_friday_status(&settings, saturday.to_utc()),
+        FridayStatus::Countdown(Duration::days(6))
+    );
+}
+
+#[test]
+fn friday_status_custom_settings_test() {
+    use chrono::NaiveTime;
+    use chrono_tz::Asia::Yekaterinburg;
+    use teloxide::types::ChatId;
+
+    let mut settings = ChatSettings::new(ChatId(1));
+    settings.timezone = Yekaterinburg;
+    settings.start_time = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+    settings.end_time = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+
+    let friday_morning = Yekaterinburg
+        .with_ymd_and_hms(2026, 10, 23, 10, 0, 0)
+        .unwrap();
+    let friday_evening = Yekaterinburg
+        .with_ymd_and_hms(2026, 10, 23, 19, 0, 0)
+        .unwrap();
+    let friday_night = Yekaterinburg
+        .with_ymd_and_hms(2026, 10, 23, 23, 30, 0)
+        .unwrap();
+
+    assert_eq!(
+        get_friday_status(&settings, friday_morning.to_utc()),
+        FridayStatus::Countdown(Duration::hours(8))
+    );
+    assert_eq!(
+        get_friday_status(&settings, friday_evening.to_utc()),
+        FridayStatus::Started
+    );
+    assert_eq!(
+        get_friday_status(&settings, friday_night.to_utc()),
+        FridayStatus::Over
+    );
+}
+
+#[test]
+fn friday_status_dst_gap_test() {
+    use chrono::{NaiveTime, Weekday};
+    use chrono_tz::Europe::Berlin;
+    use teloxide::types::ChatId;
+
+    // On 2026-03-29 clocks in Berlin jump from 02:00 straight to 03:00
+    let mut settings = ChatSettings::new(ChatId(1));
+    settings.timezone = Berlin;
+    settings.weekday = Weekday::Sun;
+    settings.start_time = NaiveTime::from_hms_opt(2, 0, 0).unwrap();
+    settings.end_time = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+
+    let before_jump = Berlin.with_ymd_and_hms(2026, 3, 29, 1, 30, 0).unwrap();
+    let after_jump = Berlin.with_ymd_and_hms(2026, 3, 29, 3, 30, 0).unwrap();
+
+    assert_eq!(
+        get_friday_status(&settings, before_jump.to_utc()),
+        FridayStatus::Countdown(Duration::minutes(30))
+    );
+    assert_eq!(
+        get_friday_status(&settings, after_jump.to_utc()),
+        FridayStatus::Started
+    );
+}